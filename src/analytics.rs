@@ -0,0 +1,204 @@
+//! Module implement statistics over a local [Archive], for studying the
+//! beacon.
+//!
+//! * Rounds per day, bucketed by the round's scheduled time, refer
+//!   [Info::round_time], as days since Unix Epoch.
+//! * Missing rounds, gaps between the first and the latest archived round.
+//! * Publish-delay histogram, delay between the round's scheduled time and
+//!   the time it was observed, for rounds inserted via
+//!   [Archive::insert_observed].
+//! * Bit-distribution of randomness, with monobit and per-position
+//!   z-scores. For a uniform source, z-scores are standard normal, values
+//!   beyond 4 or so are worth a closer look.
+
+use std::{collections::BTreeMap, ops, time};
+
+use crate::{Archive, Info, Result};
+
+const SECS_PER_DAY: u64 = 86400;
+// histogram buckets, delays beyond them are counted as late.
+const MAX_BUCKETS: usize = 1024;
+
+/// Return number of archived rounds for each day, keyed by days since
+/// Unix Epoch.
+pub fn rounds_per_day(archive: &Archive, info: &Info) -> Result<BTreeMap<u64, usize>> {
+    let mut days = BTreeMap::new();
+    for r in archive.iter() {
+        let t = info.round_time(r.round)?;
+        let secs = err_at!(Invalid, t.duration_since(time::UNIX_EPOCH))?.as_secs();
+        *days.entry(secs / SECS_PER_DAY).or_insert(0) += 1;
+    }
+    Ok(days)
+}
+
+/// Return rounds missing between the first and the latest archived
+/// round, as ascending list of ranges.
+pub fn missing_rounds(archive: &Archive) -> Vec<ops::RangeInclusive<u128>> {
+    let mut gaps = vec![];
+    let mut iter = archive.iter().map(|r| r.round);
+    if let Some(mut prev) = iter.next() {
+        for round in iter {
+            if round > prev + 1 {
+                gaps.push((prev + 1)..=(round - 1));
+            }
+            prev = round;
+        }
+    }
+    gaps
+}
+
+/// Histogram of publish delays, refer [publish_delays].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Histogram {
+    /// Width of each bucket.
+    pub bucket: time::Duration,
+    /// `counts[i]` is the number of rounds observed with delay in
+    /// `[i * bucket, (i + 1) * bucket)`, for at most 1024 buckets.
+    pub counts: Vec<usize>,
+    /// Number of rounds observed before their scheduled time, typically
+    /// due to clock skew.
+    pub early: usize,
+    /// Number of rounds observed with delay beyond the last bucket, that
+    /// is, at or after `1024 * bucket`.
+    pub late: usize,
+}
+
+impl Histogram {
+    /// Return total number of samples, including early ones.
+    pub fn len(&self) -> usize {
+        self.counts.iter().sum::<usize>() + self.early + self.late
+    }
+
+    /// Return whether histogram has no samples.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the upper bound of the bucket that holds the `p`th
+    /// percentile, `p` in the range `[0, 100]`. Early samples count as
+    /// zero delay. Return None if there are no samples, or if the
+    /// percentile falls among late samples.
+    pub fn to_percentile(&self, p: f64) -> Option<time::Duration> {
+        let n = self.len();
+        if n == 0 {
+            return None;
+        }
+        let p = p.max(0.0).min(100.0);
+        let rank = ((p / 100.0) * (n as f64)).ceil().max(1.0) as usize;
+
+        let mut seen = self.early;
+        if seen >= rank {
+            return Some(time::Duration::default());
+        }
+        for (i, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return Some(self.bucket * ((i + 1) as u32));
+            }
+        }
+        None
+    }
+}
+
+/// Reconstruct the histogram of publish delays, from rounds whose
+/// observed time is recorded in the archive. Rounds without observed time
+/// are skipped.
+pub fn publish_delays(
+    archive: &Archive,
+    info: &Info,
+    bucket: time::Duration,
+) -> Result<Histogram> {
+    if bucket.as_nanos() == 0 {
+        err_at!(Invalid, msg: format!("histogram bucket cannot be zero"))?
+    }
+
+    let mut hist = Histogram {
+        bucket,
+        counts: vec![],
+        early: 0,
+        late: 0,
+    };
+    for r in archive.iter() {
+        let at = match archive.to_observed(r.round) {
+            Some(at) => at,
+            None => continue,
+        };
+        match at.duration_since(info.round_time(r.round)?) {
+            Ok(delay) => {
+                let i = delay.as_nanos() / bucket.as_nanos();
+                if i >= MAX_BUCKETS as u128 {
+                    hist.late += 1;
+                    continue;
+                }
+                let i = i as usize;
+                if hist.counts.len() <= i {
+                    hist.counts.resize(i + 1, 0);
+                }
+                hist.counts[i] += 1;
+            }
+            Err(_) => hist.early += 1,
+        }
+    }
+    Ok(hist)
+}
+
+/// Bit-distribution of archived randomness, refer [bit_distribution].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct BitStats {
+    /// Number of rounds sampled.
+    pub rounds: usize,
+    /// `ones[i]` is the number of rounds with bit `i` set, bits are
+    /// numbered from the most significant bit of the first byte.
+    pub ones: Vec<u64>,
+}
+
+impl BitStats {
+    /// Return total number of set bits across all rounds.
+    pub fn to_ones(&self) -> u64 {
+        self.ones.iter().sum()
+    }
+
+    /// Return the monobit z-score, deviation of total set bits from half
+    /// the number of bits, in standard deviations.
+    pub fn to_monobit_z(&self) -> f64 {
+        if self.rounds == 0 {
+            return 0.0;
+        }
+        let n = (self.rounds * self.ones.len()) as f64;
+        ((2 * self.to_ones()) as f64 - n) / n.sqrt()
+    }
+
+    /// Return the largest absolute z-score across bit positions, each
+    /// position is expected to be set in half the rounds.
+    pub fn to_max_position_z(&self) -> f64 {
+        if self.rounds == 0 {
+            return 0.0;
+        }
+        let n = self.rounds as f64;
+        let iter = self.ones.iter().map(|ones| ((2 * ones) as f64 - n).abs() / n.sqrt());
+        iter.fold(0.0, f64::max)
+    }
+}
+
+/// Return the bit-distribution of randomness across archived rounds.
+pub fn bit_distribution(archive: &Archive) -> BitStats {
+    let mut stats = BitStats {
+        rounds: 0,
+        ones: vec![0; 256],
+    };
+    for r in archive.iter() {
+        for (i, byte) in r.randomness.as_bytes().iter().enumerate() {
+            for bit in 0..8 {
+                if (byte >> (7 - bit)) & 1 == 1 {
+                    stats.ones[i * 8 + bit] += 1;
+                }
+            }
+        }
+        stats.rounds += 1;
+    }
+    stats
+}
+
+#[cfg(test)]
+#[path = "analytics_test.rs"]
+mod analytics_test;
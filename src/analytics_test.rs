@@ -0,0 +1,96 @@
+use crate::{fixtures::new_random, Random};
+
+use super::*;
+
+fn new_info() -> Info {
+    Info {
+        period: time::Duration::from_secs(30),
+        genesis_time: time::UNIX_EPOCH + time::Duration::from_secs(1595431050),
+        ..Info::default()
+    }
+}
+
+#[test]
+fn test_rounds_per_day() {
+    let info = new_info();
+    // genesis is 55050 seconds into its day, round 1046 starts the next.
+    let first_day = 1595431050 / SECS_PER_DAY;
+    let archive: Archive = (1..=1050).map(|r| new_random(r, 0)).collect();
+
+    let days = rounds_per_day(&archive, &info).unwrap();
+    let days: Vec<(u64, usize)> = days.into_iter().collect();
+    assert_eq!(days, vec![(first_day, 1045), (first_day + 1, 5)]);
+}
+
+#[test]
+fn test_missing_rounds() {
+    assert!(missing_rounds(&Archive::default()).is_empty());
+
+    let archive: Archive = vec![1, 2, 5, 6, 7, 10]
+        .into_iter()
+        .map(|r| new_random(r, 0))
+        .collect();
+    assert_eq!(missing_rounds(&archive), vec![3..=4, 8..=9]);
+}
+
+#[test]
+fn test_publish_delays() {
+    let info = new_info();
+    let mut archive = Archive::default();
+    // round without observed time is skipped.
+    archive.insert(new_random(1, 0));
+    for (round, millis) in [(2, 200), (3, 700), (4, 1200), (5, 250)].iter() {
+        let at = info.round_time(*round).unwrap() + time::Duration::from_millis(*millis);
+        archive.insert_observed(new_random(*round, 0), at);
+    }
+    let at = info.round_time(6).unwrap() - time::Duration::from_millis(100);
+    archive.insert_observed(new_random(6, 0), at);
+
+    let bucket = time::Duration::from_millis(500);
+    let hist = publish_delays(&archive, &info, bucket).unwrap();
+    assert_eq!(hist.counts, vec![2, 1, 1]);
+    assert_eq!(hist.early, 1);
+    assert_eq!(hist.len(), 5);
+    assert_eq!(hist.to_percentile(0.0), Some(time::Duration::default()));
+    assert_eq!(hist.to_percentile(50.0), Some(bucket));
+    assert_eq!(hist.to_percentile(100.0), Some(bucket * 3));
+
+    // delays beyond the last bucket are counted as late.
+    let at = info.round_time(7).unwrap() + bucket * 2000;
+    archive.insert_observed(new_random(7, 0), at);
+    let hist = publish_delays(&archive, &info, bucket).unwrap();
+    assert_eq!(hist.counts, vec![2, 1, 1]);
+    assert_eq!(hist.late, 1);
+    assert_eq!(hist.len(), 6);
+    assert_eq!(hist.to_percentile(100.0), None);
+
+    assert!(publish_delays(&archive, &info, time::Duration::default()).is_err());
+    let hist = publish_delays(&Archive::default(), &info, bucket).unwrap();
+    assert!(hist.is_empty());
+    assert_eq!(hist.to_percentile(50.0), None);
+}
+
+#[test]
+fn test_bit_distribution() {
+    let stats = bit_distribution(&Archive::default());
+    assert_eq!(stats.to_monobit_z(), 0.0);
+    assert_eq!(stats.to_max_position_z(), 0.0);
+
+    let archive: Archive = vec![new_random(1, 0xff), new_random(2, 0)]
+        .into_iter()
+        .collect();
+    let stats = bit_distribution(&archive);
+    assert_eq!(stats.rounds, 2);
+    assert_eq!(stats.to_ones(), 256);
+    assert_eq!(stats.to_monobit_z(), 0.0);
+    assert_eq!(stats.to_max_position_z(), 0.0);
+
+    let archive: Archive = vec![new_random(1, 0x80), new_random(2, 0x80)]
+        .into_iter()
+        .collect();
+    let stats = bit_distribution(&archive);
+    assert_eq!(stats.ones[0], 2);
+    assert_eq!(stats.ones[1], 0);
+    assert!(stats.to_monobit_z() < -10.0);
+    assert!((stats.to_max_position_z() - 2_f64.sqrt()).abs() < 1e-9);
+}
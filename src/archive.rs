@@ -0,0 +1,245 @@
+//! Module implement a local archive of randomness.
+
+use serde::{Deserialize, Serialize};
+
+use std::{collections::BTreeMap, convert::TryInto, fs, iter::FromIterator, ops, path, time};
+
+use crate::{chunked, http::RandomJson, Random, Result};
+
+/// Archive of randomness indexed by its round.
+///
+/// Archive can be persisted to a file, as json-lines, one round per line
+/// in the same format served by the drand http api. Rounds inserted via
+/// [Archive::insert_observed] additionally carry an `observed_at` field,
+/// milliseconds since Unix Epoch.
+///
+/// Archive grows with every inserted round, unless a [Retention] policy
+/// is set, refer [Archive::set_retention].
+#[derive(Clone, Default, Debug)]
+pub struct Archive {
+    rounds: BTreeMap<u128, Random>,
+    observed: BTreeMap<u128, time::SystemTime>,
+    // size of each archived round, and of all of them, when persisted as
+    // json-lines.
+    sizes: BTreeMap<u128, u64>,
+    bytes: u64,
+    retention: Option<Retention>,
+}
+
+/// Retention policy for archives, refer [Archive::set_retention] and
+/// [ChunkedArchive::prune]. Each configured limit is applied, rounds are
+/// kept only if they are within all of them. Oldest rounds are pruned
+/// first.
+///
+/// [ChunkedArchive::prune]: crate::ChunkedArchive::prune
+#[derive(Clone, Default, Eq, PartialEq, Debug)]
+pub struct Retention {
+    /// Keep the last `keep_last` rounds, counting back from the latest
+    /// archived round.
+    pub keep_last: Option<u128>,
+    /// Keep rounds after `keep_after`.
+    pub keep_after: Option<u128>,
+    /// Keep as many latest rounds as fit within `max_bytes`, when
+    /// persisted as json-lines.
+    pub max_bytes: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct ArchiveJson {
+    #[serde(flatten)]
+    random: RandomJson,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    observed_at: Option<u64>,
+}
+
+impl FromIterator<Random> for Archive {
+    fn from_iter<T: IntoIterator<Item = Random>>(iter: T) -> Self {
+        let mut archive = Archive::default();
+        for r in iter {
+            archive.insert(r);
+        }
+        archive
+    }
+}
+
+impl Archive {
+    /// Load archive from `file`, previously created by [Archive::to_file].
+    pub fn from_file<P: AsRef<path::Path>>(file: P) -> Result<Archive> {
+        let data = err_at!(IOError, fs::read_to_string(file.as_ref()))?;
+        Archive::from_jsonl(&data)
+    }
+
+    /// Persist archive to `file`, overwriting previous content.
+    pub fn to_file<P: AsRef<path::Path>>(&self, file: P) -> Result<()> {
+        let data = self.to_jsonl(self.rounds.values())?;
+        err_at!(IOError, fs::write(file.as_ref(), data))
+    }
+
+    // parse json-lines, one round per line.
+    pub(crate) fn from_jsonl(data: &str) -> Result<Archive> {
+        let mut archive = Archive::default();
+        for line in data.lines().filter(|l| !l.trim().is_empty()) {
+            let item: ArchiveJson = err_at!(JsonParse, serde_json::from_str(line))?;
+            let r: Random = item.random.try_into()?;
+            match item.observed_at {
+                Some(millis) => {
+                    let at = time::UNIX_EPOCH.checked_add(time::Duration::from_millis(millis));
+                    match at {
+                        Some(at) => archive.insert_observed(r, at),
+                        None => err_at!(Invalid, msg: format!("observed_at {}", millis))?,
+                    };
+                }
+                None => {
+                    archive.insert(r);
+                }
+            }
+        }
+        Ok(archive)
+    }
+
+    // format `rounds` as json-lines, along with their observed time.
+    pub(crate) fn to_jsonl<'a, I>(&self, rounds: I) -> Result<String>
+    where
+        I: Iterator<Item = &'a Random>,
+    {
+        let mut data = String::default();
+        for r in rounds {
+            data.push_str(&self.to_line(r)?);
+            data.push('\n');
+        }
+        Ok(data)
+    }
+
+    fn to_line(&self, r: &Random) -> Result<String> {
+        let observed_at = self.observed.get(&r.round).map(|at| {
+            let millis = at.duration_since(time::UNIX_EPOCH).unwrap_or_default();
+            millis.as_millis() as u64
+        });
+        let item = ArchiveJson {
+            random: RandomJson::from(r),
+            observed_at,
+        };
+        err_at!(JsonParse, serde_json::to_string(&item))
+    }
+
+    /// Persist archive into directory `dir`, in chunks of `chunk_rounds`
+    /// rounds, along with an index of chunks by round range. Chunks are
+    /// compressed with zstd, if the `zstd` feature is enabled. Refer
+    /// [ChunkedArchive] to lookup rounds without loading the whole archive.
+    ///
+    /// [ChunkedArchive]: crate::ChunkedArchive
+    pub fn to_chunks<P: AsRef<path::Path>>(&self, dir: P, chunk_rounds: u128) -> Result<()> {
+        chunked::save(self, dir.as_ref(), chunk_rounds)
+    }
+
+    /// Return archived rounds within `range`, in ascending order.
+    pub fn range(&self, range: ops::RangeInclusive<u128>) -> impl Iterator<Item = &Random> {
+        self.rounds.range(range).map(|(_, r)| r)
+    }
+
+    /// Insert a round of randomness into archive, return the previous
+    /// entry for the same round, if any. If retention policy is set,
+    /// archive is pruned after inserting.
+    pub fn insert(&mut self, r: Random) -> Option<Random> {
+        self.insert_at(r, None)
+    }
+
+    /// Same as [Archive::insert], additionally record the time at which
+    /// the round was observed, typically the time it was received from a
+    /// relay.
+    pub fn insert_observed(&mut self, r: Random, at: time::SystemTime) -> Option<Random> {
+        self.insert_at(r, Some(at))
+    }
+
+    fn insert_at(&mut self, r: Random, at: Option<time::SystemTime>) -> Option<Random> {
+        let round = r.round;
+        if let Some(at) = at {
+            self.observed.insert(round, at);
+        }
+        // size when persisted, including the newline.
+        let size = match self.to_line(&r) {
+            Ok(line) => (line.len() as u64) + 1,
+            Err(_) => 0,
+        };
+        let old_size = self.sizes.insert(round, size).unwrap_or(0);
+        let old = self.rounds.insert(round, r);
+        self.bytes = self.bytes.saturating_sub(old_size) + size;
+
+        if let Some(retention) = self.retention.clone() {
+            self.prune(&retention);
+        }
+        old
+    }
+
+    /// Set retention policy, applied on every insert, so that an archive
+    /// fed by a long-running watch does not grow without bound. Archive
+    /// is pruned right away. Refer [Archive::prune] to prune once.
+    pub fn set_retention(&mut self, retention: Option<Retention>) -> &mut Self {
+        if let Some(retention) = retention.as_ref() {
+            self.prune(retention);
+        }
+        self.retention = retention;
+        self
+    }
+
+    /// Remove rounds outside `retention`, oldest first. Return the number
+    /// of rounds removed.
+    pub fn prune(&mut self, retention: &Retention) -> usize {
+        let mut n = 0;
+        while let Some(round) = self.rounds.keys().next().cloned() {
+            let latest = self.rounds.keys().next_back().cloned().unwrap_or(round);
+            let prune = matches!(retention.keep_after, Some(x) if round <= x)
+                || matches!(retention.keep_last, Some(m) if latest - round >= m)
+                || matches!(retention.max_bytes, Some(b) if self.bytes > b);
+            if !prune {
+                break;
+            }
+            let size = self.sizes.remove(&round).unwrap_or(0);
+            self.bytes = self.bytes.saturating_sub(size);
+            self.rounds.remove(&round);
+            self.observed.remove(&round);
+            n += 1;
+        }
+        n
+    }
+
+    /// Return the size of archive, in bytes, when persisted as
+    /// json-lines by [Archive::to_file].
+    pub fn to_size(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Return the time at which `round` was observed, if recorded.
+    pub fn to_observed(&self, round: u128) -> Option<time::SystemTime> {
+        self.observed.get(&round).cloned()
+    }
+
+    /// Return randomness for `round`, if archived.
+    pub fn get(&self, round: u128) -> Option<&Random> {
+        self.rounds.get(&round)
+    }
+
+    /// Return the latest archived round of randomness.
+    pub fn to_latest(&self) -> Option<&Random> {
+        self.rounds.values().next_back()
+    }
+
+    /// Iterate over archived randomness, in ascending order of rounds.
+    pub fn iter(&self) -> impl Iterator<Item = &Random> {
+        self.rounds.values()
+    }
+
+    /// Return number of archived rounds.
+    pub fn len(&self) -> usize {
+        self.rounds.len()
+    }
+
+    /// Return whether archive is empty.
+    pub fn is_empty(&self) -> bool {
+        self.rounds.is_empty()
+    }
+}
+
+#[cfg(test)]
+#[path = "archive_test.rs"]
+mod archive_test;
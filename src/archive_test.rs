@@ -0,0 +1,104 @@
+use crate::fixtures::mainnet_round1;
+
+use super::*;
+
+#[test]
+fn test_archive() {
+    let r1 = mainnet_round1();
+    let r5 = Random {
+        round: 5,
+        ..r1.clone()
+    };
+
+    let archive: Archive = vec![r5.clone(), r1.clone()].into_iter().collect();
+    assert_eq!(archive.len(), 2);
+    assert_eq!(archive.get(1), Some(&r1));
+    assert_eq!(archive.get(2), None);
+    assert_eq!(archive.to_latest(), Some(&r5));
+    let rounds: Vec<u128> = archive.iter().map(|r| r.round).collect();
+    assert_eq!(rounds, vec![1, 5]);
+
+    let file = std::env::temp_dir().join("drand-rs-test-archive.jsonl");
+    archive.to_file(&file).unwrap();
+    let loaded = Archive::from_file(&file).unwrap();
+    assert_eq!(loaded.iter().collect::<Vec<&Random>>(), archive.iter().collect::<Vec<&Random>>());
+    std::fs::remove_file(&file).unwrap();
+
+    assert!(Archive::from_file(&file).is_err());
+}
+
+#[test]
+fn test_archive_observed() {
+    let r1 = mainnet_round1();
+    let r2 = Random {
+        round: 2,
+        ..r1.clone()
+    };
+    let at = time::UNIX_EPOCH + time::Duration::from_millis(1595431050_250);
+
+    let mut archive = Archive::default();
+    archive.insert(r1.clone());
+    archive.insert_observed(r2.clone(), at);
+    assert_eq!(archive.to_observed(1), None);
+    assert_eq!(archive.to_observed(2), Some(at));
+
+    let file = std::env::temp_dir().join("drand-rs-test-archive-observed.jsonl");
+    archive.to_file(&file).unwrap();
+    let loaded = Archive::from_file(&file).unwrap();
+    std::fs::remove_file(&file).unwrap();
+
+    assert_eq!(loaded.get(1), Some(&r1));
+    assert_eq!(loaded.get(2), Some(&r2));
+    assert_eq!(loaded.to_observed(1), None);
+    assert_eq!(loaded.to_observed(2), Some(at));
+}
+
+#[test]
+fn test_archive_retention() {
+    let r1 = mainnet_round1();
+    let new_round = |round| Random {
+        round,
+        ..r1.clone()
+    };
+
+    let mut archive: Archive = (10..=19).map(new_round).collect();
+    let file = std::env::temp_dir().join("drand-rs-test-archive-retention.jsonl");
+    archive.to_file(&file).unwrap();
+    let size = std::fs::metadata(&file).unwrap().len();
+    std::fs::remove_file(&file).unwrap();
+    assert_eq!(archive.to_size(), size);
+    // rounds of same width, every line is of same size.
+    let line = size / 10;
+
+    let retention = Retention {
+        keep_after: Some(12),
+        ..Retention::default()
+    };
+    assert_eq!(archive.prune(&retention), 3);
+    assert_eq!(archive.iter().next().map(|r| r.round), Some(13));
+    assert_eq!(archive.to_size(), line * 7);
+
+    let retention = Retention {
+        keep_last: Some(5),
+        ..Retention::default()
+    };
+    assert_eq!(archive.prune(&retention), 2);
+    assert_eq!(archive.iter().next().map(|r| r.round), Some(15));
+
+    // applied on every insert.
+    let retention = Retention {
+        max_bytes: Some(line * 3),
+        ..Retention::default()
+    };
+    archive.set_retention(Some(retention));
+    assert_eq!(archive.len(), 3);
+    archive.insert(new_round(20));
+    archive.insert(new_round(21));
+    let rounds: Vec<u128> = archive.iter().map(|r| r.round).collect();
+    assert_eq!(rounds, vec![19, 20, 21]);
+    assert_eq!(archive.to_size(), line * 3);
+
+    archive.set_retention(None);
+    archive.insert(new_round(22));
+    assert_eq!(archive.len(), 4);
+}
@@ -0,0 +1,101 @@
+//! Module implement retry policy shared by the client's retry loops,
+//! [Client::wait_for_round] and watch, and the latency penalty applied to
+//! endpoints on failed requests.
+//!
+//! Delays are deterministic, there is no jitter, and sleeping goes through
+//! [Clock::sleep], so that applications can simulate time in tests.
+//!
+//! [Client::wait_for_round]: crate::Client::wait_for_round
+
+use std::{cmp, time};
+
+use crate::{clock::Clock, http::MAX_ELAPSED, Error};
+
+/// Exponential backoff between retries, refer [Config::backoff]. Delay
+/// before `n`th retry, `n` starting from 0, is `initial * factor^n`,
+/// capped at `max`.
+///
+/// [Config::backoff]: crate::Config::backoff
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Backoff {
+    /// Delay before the first retry.
+    pub initial: time::Duration,
+    /// Multiplier applied to the delay on every retry.
+    pub factor: u32,
+    /// Upper bound on the delay.
+    pub max: time::Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Backoff {
+            initial: time::Duration::from_millis(100),
+            factor: 2,
+            max: time::Duration::from_secs(30),
+        }
+    }
+}
+
+impl Backoff {
+    /// Return the delay before `n`th retry.
+    pub fn to_delay(&self, n: u32) -> time::Duration {
+        let mut delay = cmp::min(self.initial, self.max);
+        for _ in 0..n {
+            delay = match delay.checked_mul(self.factor) {
+                Some(delay) if delay < self.max => delay,
+                _ => return self.max,
+            };
+        }
+        delay
+    }
+
+    /// Return the delay before `n`th retry, after failing with `err`.
+    /// When relay tells when the round shall be available, or when to
+    /// retry after rate limiting, that is used instead, still capped at
+    /// `max`.
+    pub fn to_retry_delay(&self, n: u32, err: &Error) -> time::Duration {
+        match err {
+            Error::RoundNotYetAvailable(_, _, Some(after)) => cmp::min(*after, self.max),
+            Error::RateLimited(_, Some(after)) => cmp::min(*after, self.max),
+            _ => self.to_delay(n),
+        }
+    }
+}
+
+// State of a retry loop, counts retries and sleeps between them.
+pub(crate) struct Retry<'a> {
+    backoff: &'a Backoff,
+    clock: &'a dyn Clock,
+    n: u32,
+}
+
+impl<'a> Retry<'a> {
+    pub(crate) fn new(backoff: &'a Backoff, clock: &'a dyn Clock) -> Retry<'a> {
+        Retry { backoff, clock, n: 0 }
+    }
+
+    // delay before next retry, after failing with `err`, capped at `cap`.
+    pub(crate) fn to_delay(&self, err: &Error, cap: time::Duration) -> time::Duration {
+        cmp::min(self.backoff.to_retry_delay(self.n, err), cap)
+    }
+
+    // sleep for `delay`, and count the retry.
+    pub(crate) fn sleep(&mut self, delay: time::Duration) {
+        self.n = self.n.saturating_add(1);
+        self.clock.sleep(delay)
+    }
+}
+
+// Latency recorded for an endpoint on a failed request, double of its
+// average latency `elapsed`, so that repeated failures push the endpoint
+// behind healthier endpoints in endpoint selection.
+pub(crate) fn to_penalty(elapsed: time::Duration) -> time::Duration {
+    match elapsed.checked_mul(2) {
+        Some(elapsed) => cmp::min(elapsed, MAX_ELAPSED),
+        None => MAX_ELAPSED,
+    }
+}
+
+#[cfg(test)]
+#[path = "backoff_test.rs"]
+mod backoff_test;
@@ -0,0 +1,76 @@
+use std::sync::Mutex;
+
+use super::*;
+
+#[derive(Debug, Default)]
+struct SimClock {
+    slept: Mutex<Vec<time::Duration>>,
+}
+
+impl Clock for SimClock {
+    fn now(&self) -> time::SystemTime {
+        let slept: time::Duration = self.slept.lock().unwrap().iter().sum();
+        time::UNIX_EPOCH + slept
+    }
+
+    fn sleep(&self, dur: time::Duration) {
+        self.slept.lock().unwrap().push(dur)
+    }
+}
+
+#[test]
+fn test_backoff() {
+    let ms = time::Duration::from_millis;
+
+    let backoff = Backoff::default();
+    let delays: Vec<time::Duration> = (0..4).map(|n| backoff.to_delay(n)).collect();
+    assert_eq!(delays, vec![ms(100), ms(200), ms(400), ms(800)]);
+    assert_eq!(backoff.to_delay(20), backoff.max);
+    assert_eq!(backoff.to_delay(u32::MAX), backoff.max);
+
+    let backoff = Backoff {
+        initial: ms(10),
+        factor: 3,
+        max: ms(100),
+    };
+    let delays: Vec<time::Duration> = (0..4).map(|n| backoff.to_delay(n)).collect();
+    assert_eq!(delays, vec![ms(10), ms(30), ms(90), ms(100)]);
+
+    let err = Error::RoundNotYetAvailable("".to_string(), 10, Some(ms(50)));
+    assert_eq!(backoff.to_retry_delay(3, &err), ms(50));
+    let err = Error::RoundNotYetAvailable("".to_string(), 10, Some(ms(5000)));
+    assert_eq!(backoff.to_retry_delay(0, &err), ms(100));
+    let err = Error::RoundNotYetAvailable("".to_string(), 10, None);
+    assert_eq!(backoff.to_retry_delay(1, &err), ms(30));
+    let err = Error::IOError("".to_string(), "".to_string());
+    assert_eq!(backoff.to_retry_delay(2, &err), ms(90));
+    let err = Error::RateLimited("".to_string(), Some(ms(70)));
+    assert_eq!(backoff.to_retry_delay(0, &err), ms(70));
+    let err = Error::RateLimited("".to_string(), None);
+    assert_eq!(backoff.to_retry_delay(0, &err), ms(10));
+}
+
+#[test]
+fn test_retry() {
+    let ms = time::Duration::from_millis;
+
+    let (backoff, clock) = (Backoff::default(), SimClock::default());
+    let mut retry = Retry::new(&backoff, &clock);
+    let err = Error::IOError("".to_string(), "".to_string());
+    for _ in 0..4 {
+        retry.sleep(retry.to_delay(&err, ms(300)));
+    }
+    let slept = clock.slept.lock().unwrap().clone();
+    assert_eq!(slept, vec![ms(100), ms(200), ms(300), ms(300)]);
+    assert_eq!(clock.now(), time::UNIX_EPOCH + ms(900));
+}
+
+#[test]
+fn test_penalty() {
+    let secs = time::Duration::from_secs;
+
+    assert_eq!(to_penalty(secs(10)), secs(20));
+    assert_eq!(to_penalty(MAX_ELAPSED), MAX_ELAPSED);
+    // endpoints without latency samples report u64::MAX seconds.
+    assert_eq!(to_penalty(secs(u64::MAX)), MAX_ELAPSED);
+}
@@ -0,0 +1,144 @@
+//! Module implement circuit breaker for endpoints, refer [Config::breaker].
+//!
+//! Circuit of an endpoint is closed to begin with. After `failures`
+//! consecutive failures it opens, and the endpoint is left out of
+//! endpoint selection. Once `cool_off` has elapsed, circuit is half-open
+//! and the endpoint is selected again, as a probe, behind endpoints with
+//! closed circuit. A failure while half-open opens the circuit again, and
+//! `successes` consecutive successes close it.
+//!
+//! Missing rounds and rounds not yet available are not counted as
+//! failures, they are not the endpoint's fault. Neither is rate limiting,
+//! an endpoint responding with 429 is paused for its Retry-After duration,
+//! and selected again once the pause is over, irrespective of its circuit.
+//!
+//! [Config::breaker]: crate::Config::breaker
+
+use std::time;
+
+/// Thresholds of the circuit breaker applied to every endpoint, refer
+/// [Config::breaker].
+///
+/// [Config::breaker]: crate::Config::breaker
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Breaker {
+    /// Open the circuit after these many consecutive failures.
+    pub failures: u32,
+    /// Half-open the circuit, after it is open for this long.
+    pub cool_off: time::Duration,
+    /// Close a half-open circuit after these many consecutive successes.
+    pub successes: u32,
+}
+
+impl Default for Breaker {
+    fn default() -> Self {
+        Breaker {
+            failures: 5,
+            cool_off: time::Duration::from_secs(30),
+            successes: 2,
+        }
+    }
+}
+
+/// State of an endpoint's circuit, refer [Breaker].
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum CircuitState {
+    /// Endpoint is selected as usual.
+    Closed,
+    /// Endpoint is left out of endpoint selection.
+    Open,
+    /// Endpoint is selected as a probe, behind closed endpoints.
+    HalfOpen,
+}
+
+impl Default for CircuitState {
+    fn default() -> Self {
+        CircuitState::Closed
+    }
+}
+
+// Circuit of a single endpoint.
+#[derive(Clone, Default, Debug)]
+pub(crate) struct Circuit {
+    // consecutive failures while closed.
+    failures: u32,
+    // consecutive successes while half-open.
+    successes: u32,
+    // time the circuit opened, None if closed.
+    opened_at: Option<time::SystemTime>,
+    // rate limited by the endpoint, until this time.
+    paused_until: Option<time::SystemTime>,
+}
+
+impl Circuit {
+    pub(crate) fn to_state(&self, breaker: &Breaker, now: time::SystemTime) -> CircuitState {
+        match self.opened_at {
+            None => CircuitState::Closed,
+            Some(at) => match now.duration_since(at) {
+                Ok(elapsed) if elapsed >= breaker.cool_off => CircuitState::HalfOpen,
+                _ => CircuitState::Open,
+            },
+        }
+    }
+
+    // whether endpoint is paused, after rate limiting.
+    pub(crate) fn is_paused(&self, now: time::SystemTime) -> bool {
+        matches!(self.paused_until, Some(until) if now < until)
+    }
+
+    // pause endpoint for `pause`, after it rate limited requests.
+    pub(crate) fn pause(&mut self, now: time::SystemTime, pause: time::Duration) {
+        self.paused_until = now.checked_add(pause);
+    }
+
+    // record a success, return whether the circuit closed.
+    pub(crate) fn on_success(&mut self, breaker: &Breaker, now: time::SystemTime) -> bool {
+        self.failures = 0;
+        match self.to_state(breaker, now) {
+            CircuitState::Closed => false,
+            // late response to a request sent before the circuit opened.
+            CircuitState::Open => false,
+            CircuitState::HalfOpen => {
+                self.successes = self.successes.saturating_add(1);
+                if self.successes >= breaker.successes {
+                    *self = Circuit::default();
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    // record a failure, return whether the circuit opened.
+    pub(crate) fn on_failure(&mut self, breaker: &Breaker, now: time::SystemTime) -> bool {
+        match self.to_state(breaker, now) {
+            CircuitState::Closed => {
+                self.failures = self.failures.saturating_add(1);
+                if self.failures >= breaker.failures {
+                    self.open(now);
+                    true
+                } else {
+                    false
+                }
+            }
+            CircuitState::Open => false,
+            CircuitState::HalfOpen => {
+                self.open(now);
+                true
+            }
+        }
+    }
+
+    fn open(&mut self, now: time::SystemTime) {
+        *self = Circuit {
+            opened_at: Some(now),
+            paused_until: self.paused_until,
+            ..Circuit::default()
+        };
+    }
+}
+
+#[cfg(test)]
+#[path = "breaker_test.rs"]
+mod breaker_test;
@@ -0,0 +1,66 @@
+use super::*;
+
+#[test]
+fn test_circuit() {
+    let secs = time::Duration::from_secs;
+
+    let breaker = Breaker {
+        failures: 3,
+        cool_off: secs(10),
+        successes: 2,
+    };
+    let t0 = time::UNIX_EPOCH + secs(1000);
+
+    let mut circuit = Circuit::default();
+    assert_eq!(circuit.to_state(&breaker, t0), CircuitState::Closed);
+
+    // a success resets consecutive failures.
+    assert!(!circuit.on_failure(&breaker, t0));
+    assert!(!circuit.on_failure(&breaker, t0));
+    assert!(!circuit.on_success(&breaker, t0));
+    assert!(!circuit.on_failure(&breaker, t0));
+    assert!(!circuit.on_failure(&breaker, t0));
+    assert!(circuit.on_failure(&breaker, t0));
+    assert_eq!(circuit.to_state(&breaker, t0), CircuitState::Open);
+    assert_eq!(circuit.to_state(&breaker, t0 + secs(9)), CircuitState::Open);
+    // failures and late successes while open are ignored.
+    assert!(!circuit.on_failure(&breaker, t0 + secs(5)));
+    assert!(!circuit.on_success(&breaker, t0 + secs(5)));
+
+    // half-open after cool-off, a failed probe opens it again.
+    let t1 = t0 + secs(10);
+    assert_eq!(circuit.to_state(&breaker, t1), CircuitState::HalfOpen);
+    assert!(circuit.on_failure(&breaker, t1));
+    assert_eq!(circuit.to_state(&breaker, t1 + secs(1)), CircuitState::Open);
+
+    // successful probes close it.
+    let t2 = t1 + secs(10);
+    assert_eq!(circuit.to_state(&breaker, t2), CircuitState::HalfOpen);
+    assert!(!circuit.on_success(&breaker, t2));
+    assert_eq!(circuit.to_state(&breaker, t2), CircuitState::HalfOpen);
+    assert!(circuit.on_success(&breaker, t2));
+    assert_eq!(circuit.to_state(&breaker, t2), CircuitState::Closed);
+}
+
+#[test]
+fn test_circuit_pause() {
+    let secs = time::Duration::from_secs;
+
+    let breaker = Breaker::default();
+    let t0 = time::UNIX_EPOCH + secs(1000);
+
+    let mut circuit = Circuit::default();
+    assert!(!circuit.is_paused(t0));
+    circuit.pause(t0, secs(5));
+    assert!(circuit.is_paused(t0));
+    assert!(circuit.is_paused(t0 + secs(4)));
+    assert!(!circuit.is_paused(t0 + secs(5)));
+    assert_eq!(circuit.to_state(&breaker, t0), CircuitState::Closed);
+
+    // pause outlives the circuit opening.
+    for _ in 0..breaker.failures {
+        circuit.on_failure(&breaker, t0);
+    }
+    assert_eq!(circuit.to_state(&breaker, t0), CircuitState::Open);
+    assert!(circuit.is_paused(t0 + secs(1)));
+}
@@ -0,0 +1,252 @@
+//! Module implement chunked storage of archives, refer [Archive::to_chunks].
+//!
+//! Directory holds an `index.json` and one file per chunk. Chunk `k`
+//! holds rounds `k*n+1..=(k+1)*n`, for `n` rounds per chunk, as
+//! json-lines, same as [Archive::to_file]. Chunk holding a round is
+//! computed from the round number, so that looking up a round reads a
+//! single chunk, irrespective of the size of the archive.
+//!
+//! With the `zstd` feature, chunks are compressed with zstd, and named
+//! with `.jsonl.zst` extension. Uncompressed chunks can be read with or
+//! without the feature.
+
+use serde::{Deserialize, Serialize};
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs, ops, path,
+};
+
+use crate::{statedir::write_atomic, Archive, Random, Result, Retention};
+
+const INDEX_FILE: &str = "index.json";
+
+#[cfg(feature = "zstd")]
+const ZSTD_LEVEL: i32 = 3;
+
+#[derive(Clone, Deserialize, Serialize)]
+struct IndexJson {
+    chunk_rounds: u128,
+    chunks: Vec<ChunkJson>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+struct ChunkJson {
+    from: u128,
+    till: u128,
+    file: String,
+    compressed: bool,
+}
+
+/// Archive persisted in chunks, by [Archive::to_chunks], for looking up
+/// rounds without loading the whole archive. Most recently read chunk is
+/// kept in memory.
+pub struct ChunkedArchive {
+    dir: path::PathBuf,
+    chunk_rounds: u128,
+    // chunk number to chunk.
+    chunks: BTreeMap<u128, ChunkJson>,
+    cache: Option<(u128, Archive)>,
+}
+
+impl ChunkedArchive {
+    /// Open archive persisted in directory `dir`.
+    pub fn open<P: AsRef<path::Path>>(dir: P) -> Result<ChunkedArchive> {
+        let dir = dir.as_ref().to_path_buf();
+        let data = err_at!(IOError, fs::read_to_string(dir.join(INDEX_FILE)))?;
+        let index: IndexJson = err_at!(JsonParse, serde_json::from_str(&data))?;
+        if index.chunk_rounds == 0 {
+            err_at!(Invalid, msg: format!("chunk of zero rounds in {:?}", dir))?
+        }
+
+        let n = index.chunk_rounds;
+        let chunks = index.chunks.into_iter().map(|c| (c.from.saturating_sub(1) / n, c));
+        Ok(ChunkedArchive {
+            dir,
+            chunk_rounds: n,
+            chunks: chunks.collect(),
+            cache: None,
+        })
+    }
+
+    /// Return randomness for `round`, if archived.
+    pub fn get(&mut self, round: u128) -> Result<Option<Random>> {
+        if round == 0 {
+            return Ok(None);
+        }
+        let k = (round - 1) / self.chunk_rounds;
+        match &self.cache {
+            Some((n, _)) if *n == k => (),
+            _ => match self.chunks.get(&k) {
+                Some(chunk) => {
+                    let archive = load(&self.dir, chunk)?;
+                    self.cache = Some((k, archive));
+                }
+                None => return Ok(None),
+            },
+        }
+        Ok(self.cache.as_ref().and_then(|(_, a)| a.get(round).cloned()))
+    }
+
+    /// Return the range of rounds covered by archived chunks.
+    pub fn to_range(&self) -> Option<ops::RangeInclusive<u128>> {
+        let from = self.chunks.values().next()?.from;
+        let till = self.chunks.values().next_back()?.till;
+        Some(from..=till)
+    }
+
+    /// Return number of rounds per chunk.
+    pub fn to_chunk_rounds(&self) -> u128 {
+        self.chunk_rounds
+    }
+
+    /// Remove chunks outside `retention`, oldest first, and return the
+    /// number of chunks removed. Chunks are removed whole, a chunk is
+    /// kept if any of its rounds is within `retention`, and `max_bytes`
+    /// is matched against the size of chunk files on disk. Removed chunks
+    /// are dropped from the index before their files are deleted.
+    pub fn prune(&mut self, retention: &Retention) -> Result<usize> {
+        let last = match self.chunks.keys().next_back().cloned() {
+            Some(k) => k,
+            None => return Ok(0),
+        };
+        let latest = self.to_latest_round(last)?;
+        let mut sizes = BTreeMap::new();
+        for (k, chunk) in self.chunks.iter() {
+            let meta = err_at!(IOError, fs::metadata(self.dir.join(&chunk.file)))?;
+            sizes.insert(*k, meta.len());
+        }
+        let mut bytes: u64 = sizes.values().sum();
+
+        let mut pruned = vec![];
+        // latest chunk is always kept.
+        for (k, chunk) in self.chunks.range(..last) {
+            let prune = matches!(retention.keep_after, Some(x) if chunk.till <= x)
+                || matches!(retention.keep_last, Some(m) if latest.saturating_sub(chunk.till) >= m)
+                || matches!(retention.max_bytes, Some(b) if bytes > b);
+            if !prune {
+                break;
+            }
+            bytes = bytes.saturating_sub(sizes[k]);
+            pruned.push(*k);
+        }
+
+        let mut files = vec![];
+        for k in pruned.iter() {
+            files.extend(self.chunks.remove(k).map(|c| c.file));
+        }
+        save_index(&self.dir, self.chunk_rounds, self.chunks.values().cloned().collect())?;
+        for file in files.into_iter() {
+            err_at!(IOError, fs::remove_file(self.dir.join(file)))?;
+        }
+        if matches!(self.cache, Some((k, _)) if pruned.contains(&k)) {
+            self.cache = None;
+        }
+        Ok(pruned.len())
+    }
+
+    fn to_latest_round(&mut self, k: u128) -> Result<u128> {
+        let chunk = &self.chunks[&k];
+        let archive = load(&self.dir, chunk)?;
+        let latest = archive.to_latest().map(|r| r.round).unwrap_or(chunk.till);
+        self.cache = Some((k, archive));
+        Ok(latest)
+    }
+
+    /// Load the whole archive into memory.
+    pub fn to_archive(&self) -> Result<Archive> {
+        let mut archive = Archive::default();
+        for chunk in self.chunks.values() {
+            let loaded = load(&self.dir, chunk)?;
+            for r in loaded.iter() {
+                match loaded.to_observed(r.round) {
+                    Some(at) => archive.insert_observed(r.clone(), at),
+                    None => archive.insert(r.clone()),
+                };
+            }
+        }
+        Ok(archive)
+    }
+}
+
+// persist `archive` into `dir`, in chunks of `n` rounds.
+pub(crate) fn save(archive: &Archive, dir: &path::Path, n: u128) -> Result<()> {
+    if n == 0 {
+        err_at!(Invalid, msg: format!("chunk of zero rounds"))?
+    }
+    if archive.get(0).is_some() {
+        err_at!(Invalid, msg: format!("round 0"))?
+    }
+    err_at!(IOError, fs::create_dir_all(dir))?;
+
+    let ks: BTreeSet<u128> = archive.iter().map(|r| (r.round - 1) / n).collect();
+
+    let mut chunks = vec![];
+    for k in ks.into_iter() {
+        let (from, till) = (k * n + 1, k.saturating_add(1).saturating_mul(n));
+        let data = archive.to_jsonl(archive.range(from..=till))?;
+        let (file, compressed) = to_file_name(k);
+        write_atomic(&dir.join(&file), &compress(data.into_bytes())?)?;
+        chunks.push(ChunkJson {
+            from,
+            till,
+            file,
+            compressed,
+        });
+    }
+
+    save_index(dir, n, chunks)
+}
+
+fn save_index(dir: &path::Path, n: u128, chunks: Vec<ChunkJson>) -> Result<()> {
+    let index = IndexJson {
+        chunk_rounds: n,
+        chunks,
+    };
+    let data = err_at!(JsonParse, serde_json::to_string(&index))?;
+    write_atomic(&dir.join(INDEX_FILE), data.as_bytes())
+}
+
+fn load(dir: &path::Path, chunk: &ChunkJson) -> Result<Archive> {
+    let data = err_at!(IOError, fs::read(dir.join(&chunk.file)))?;
+    let data = match chunk.compressed {
+        true => decompress(data)?,
+        false => data,
+    };
+    let data = err_at!(StringParse, String::from_utf8(data))?;
+    Archive::from_jsonl(&data)
+}
+
+#[cfg(feature = "zstd")]
+fn to_file_name(k: u128) -> (String, bool) {
+    (format!("chunk-{}.jsonl.zst", k), true)
+}
+
+#[cfg(not(feature = "zstd"))]
+fn to_file_name(k: u128) -> (String, bool) {
+    (format!("chunk-{}.jsonl", k), false)
+}
+
+#[cfg(feature = "zstd")]
+fn compress(data: Vec<u8>) -> Result<Vec<u8>> {
+    err_at!(IOError, zstd::encode_all(data.as_slice(), ZSTD_LEVEL))
+}
+
+#[cfg(not(feature = "zstd"))]
+fn compress(data: Vec<u8>) -> Result<Vec<u8>> {
+    Ok(data)
+}
+
+#[cfg(feature = "zstd")]
+fn decompress(data: Vec<u8>) -> Result<Vec<u8>> {
+    err_at!(IOError, zstd::decode_all(data.as_slice()))
+}
+
+#[cfg(not(feature = "zstd"))]
+fn decompress(_data: Vec<u8>) -> Result<Vec<u8>> {
+    err_at!(Invalid, msg: format!("compressed chunk, enable the zstd feature"))
+}
+
+#[cfg(test)]
+#[path = "chunked_test.rs"]
+mod chunked_test;
@@ -0,0 +1,83 @@
+use crate::fixtures::new_validated;
+
+use super::*;
+
+#[test]
+fn test_chunked_archive() {
+    use std::time;
+
+    let dir = std::env::temp_dir().join("drand-rs-test-chunked");
+    fs::remove_dir_all(&dir).ok();
+
+    // sparse archive, chunks 0, 1 and 4 of 10 rounds each.
+    let mut archive: Archive = (1..=15).chain(45..=47).map(new_validated).collect();
+    let at = time::UNIX_EPOCH + time::Duration::from_millis(1595431050_250);
+    archive.insert_observed(new_validated(46), at);
+    archive.to_chunks(&dir, 10).unwrap();
+    assert!(archive.to_chunks(&dir, 0).is_err());
+    let mut bad = archive.clone();
+    bad.insert(new_validated(0));
+    assert!(bad.to_chunks(&dir, 10).is_err());
+
+    let mut chunked = ChunkedArchive::open(&dir).unwrap();
+    assert_eq!(chunked.to_chunk_rounds(), 10);
+    assert_eq!(chunked.to_range(), Some(1..=50));
+    for round in [1, 10, 11, 15, 45, 47].iter() {
+        assert_eq!(chunked.get(*round).unwrap(), Some(new_validated(*round)));
+    }
+    for round in [0, 16, 20, 21, 44, 48, 51, 1000].iter() {
+        assert_eq!(chunked.get(*round).unwrap(), None);
+    }
+
+    let loaded = chunked.to_archive().unwrap();
+    assert_eq!(loaded.len(), archive.len());
+    assert_eq!(loaded.to_observed(46), Some(at));
+    assert_eq!(loaded.to_latest(), archive.to_latest());
+
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(ChunkedArchive::open(&dir).is_err());
+}
+
+#[test]
+fn test_chunked_prune() {
+    let dir = std::env::temp_dir().join("drand-rs-test-chunked-prune");
+    fs::remove_dir_all(&dir).ok();
+
+    let archive: Archive = (1..=45).map(new_validated).collect();
+    archive.to_chunks(&dir, 10).unwrap();
+
+    let mut chunked = ChunkedArchive::open(&dir).unwrap();
+    let retention = Retention {
+        keep_after: Some(15),
+        ..Retention::default()
+    };
+    // chunk 1..=10 is removed, chunk 11..=20 has rounds after 15.
+    assert_eq!(chunked.prune(&retention).unwrap(), 1);
+    assert_eq!(chunked.to_range(), Some(11..=50));
+    assert_eq!(chunked.get(5).unwrap(), None);
+    assert_eq!(chunked.get(11).unwrap(), Some(new_validated(11)));
+
+    let retention = Retention {
+        keep_last: Some(20),
+        ..Retention::default()
+    };
+    // latest is 45, chunks till 20 are older than the last 20 rounds.
+    assert_eq!(chunked.prune(&retention).unwrap(), 1);
+    assert_eq!(chunked.to_range(), Some(21..=50));
+
+    // latest chunk is kept, even if it exceeds max_bytes.
+    let retention = Retention {
+        max_bytes: Some(0),
+        ..Retention::default()
+    };
+    assert_eq!(chunked.prune(&retention).unwrap(), 2);
+    assert_eq!(chunked.to_range(), Some(41..=50));
+
+    // pruned index is persisted.
+    let mut chunked = ChunkedArchive::open(&dir).unwrap();
+    assert_eq!(chunked.to_range(), Some(41..=50));
+    assert_eq!(chunked.get(45).unwrap(), Some(new_validated(45)));
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
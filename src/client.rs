@@ -1,14 +1,29 @@
 //! Module implement client interface to drand-group.
 
+use tokio::sync::watch;
+
 use std::{
     cell::RefCell,
+    future::Future,
+    iter, ops,
     sync::{Arc, Mutex},
+    time,
 };
 
-use crate::{endpoints::Endpoints, Config, Error, Info, Random, Result};
+use crate::{
+    backoff::{Backoff, Retry},
+    clock::Clock,
+    endpoints::Endpoints,
+    read_handle::{Broadcast, ReadHandle},
+    singleflight,
+    verify::verify_chain,
+    Aggregate, BootPlan, BootReport, CacheHeaders, Config, DrandClient, EndpointCheck,
+    EndpointStats, Error, Event, Incident, Info, Migration, OfflineClient, Random, Randomness,
+    Relays, Result, ReverifyReport, Usage, Verdict,
+};
 
 /// List of available endpoints.
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub enum Endpoint {
     /// https://api.drand.sh
     HttpDrandApi,
@@ -18,33 +33,115 @@ pub enum Endpoint {
     HttpDrandApi3,
     /// https://drand.cloudflare.com
     HttpCloudflare,
+    /// Any other http relay, typically loaded from [Relays].
+    ///
+    /// [Relays]: crate::Relays
+    Http { url: String, category: Category },
+}
+
+impl Endpoint {
+    /// Return the endpoint's category.
+    pub fn to_category(&self) -> Category {
+        match self {
+            Endpoint::HttpDrandApi => Category::Origin,
+            Endpoint::HttpDrandApi2 => Category::Origin,
+            Endpoint::HttpDrandApi3 => Category::Origin,
+            Endpoint::HttpCloudflare => Category::Cache,
+            Endpoint::Http { category, .. } => *category,
+        }
+    }
+}
+
+/// Category of endpoints.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Category {
+    /// Relays run by the drand-group, they serve the latest rounds first.
+    Origin,
+    /// Caching proxies and CDNs in front of origin relays, preferred for
+    /// fetching historical rounds.
+    Cache,
 }
 
 /// Type to interface with league-of-entropy.
+///
+/// Client is a handle, cloned clients share the same endpoints and state,
+/// and can be used concurrently from several threads. Concurrent requests
+/// for the same round are coalesced into a single upstream request.
+#[derive(Clone)]
 pub struct Client {
     name: String,
     inner: Arc<Mutex<RefCell<InnerClient>>>,
+    flights: Arc<singleflight::Group<Option<u128>, Random>>,
+    clock: Arc<dyn Clock>,
+    backoff: Backoff,
+    fail_fast: bool,
+    early_tolerance: time::Duration,
+    late_tolerance: Option<time::Duration>,
+    // whether client is booted, refer Client::ready.
+    ready_tx: Arc<watch::Sender<bool>>,
+    ready_rx: watch::Receiver<bool>,
+    // verified state, shared with read handles.
+    broadcast: Arc<Broadcast>,
 }
 
+/// Post-processing function for randomness, refer
+/// [Client::add_post_processor].
+pub type PostProcessor = Box<dyn Fn(&Random) -> Result<Randomness> + Send>;
+
 struct InnerClient {
     _config: Config,
     endpoints: Option<Endpoints>,
+    // integrity failure that poisoned the client, in fail-fast mode.
+    incident: Option<Incident>,
+    // round served for latest round, refer Client::pin_round.
+    pinned: Option<Random>,
+    // applied to every returned round, in order.
+    post: Vec<PostProcessor>,
 }
 
 impl Client {
     /// Create a new client from `config` value, all clients are named.
     /// Caller can choose a meaningful name.
     pub fn from_config(name: &str, config: Config) -> Client {
+        let clock = Arc::clone(&config.clock);
+        let (backoff, fail_fast) = (config.backoff.clone(), config.fail_fast);
+        let (early_tolerance, late_tolerance) = (config.early_tolerance, config.late_tolerance);
+        let broadcast = Arc::new(Broadcast::new());
+        let mut endpoints = Endpoints::from_config(name, config.clone());
+        {
+            let broadcast = Arc::clone(&broadcast);
+            endpoints.add_listener(Box::new(move |event| broadcast.on_event(event)));
+        }
         let inner = InnerClient {
-            _config: config.clone(),
-            endpoints: Some(Endpoints::from_config(name, config)),
+            _config: config,
+            endpoints: Some(endpoints),
+            incident: None,
+            pinned: None,
+            post: Vec::default(),
         };
+        let (ready_tx, ready_rx) = watch::channel(false);
         Client {
             name: name.to_string(),
             inner: Arc::new(Mutex::new(RefCell::new(inner))),
+            flights: Arc::new(singleflight::Group::default()),
+            clock,
+            backoff,
+            fail_fast,
+            early_tolerance,
+            late_tolerance,
+            ready_tx: Arc::new(ready_tx),
+            ready_rx,
+            broadcast,
         }
     }
 
+    /// Return a read-only handle to this client, that serves its verified
+    /// check_point and latest round, and yields new rounds as they are
+    /// verified by this client. Refer [ReadHandle].
+    pub fn read_handle(&self) -> ReadHandle {
+        self.broadcast.to_handle(&self.name)
+    }
+
     /// Add an endpoint to the client. Typically, endpoints are added to
     /// the [Client] instance before called after its [boot] method.
     pub fn add_endpoint(&mut self, endp: Endpoint) -> Result<&mut Self> {
@@ -55,11 +152,94 @@ impl Client {
                 .endpoints
                 .as_mut()
                 .unwrap()
-                .add_endpoint(endp);
+                .add_endpoint(endp, None);
+        }
+        Ok(self)
+    }
+
+    /// Add an endpoint to the client, that shall use the supplied http
+    /// `client` for all its requests. Applications can configure proxies,
+    /// default headers, timeouts etc. on the supplied `client`. Note that
+    /// client's `max_conns` and user-agent are not applied to `client`.
+    pub fn add_endpoint_with_client(
+        &mut self,
+        endp: Endpoint,
+        client: reqwest::Client,
+    ) -> Result<&mut Self> {
+        {
+            let inner = err_at!(PoisonedLock, self.inner.lock())?;
+            inner
+                .borrow_mut()
+                .endpoints
+                .as_mut()
+                .unwrap()
+                .add_endpoint(endp, Some(client));
+        }
+        Ok(self)
+    }
+
+    /// Add all relays from `relays` as endpoints to the client, refer
+    /// [Relays::load] for loading an updated relay-list.
+    pub fn add_relays(&mut self, relays: &Relays) -> Result<&mut Self> {
+        for endp in relays.to_endpoints().into_iter() {
+            self.add_endpoint(endp)?;
+        }
+        Ok(self)
+    }
+
+    /// Register a listener for events emitted by this client. Listeners
+    /// are called synchronously, from the thread that triggered the event.
+    ///
+    /// Listeners are called while the client is locked, a listener must
+    /// not call into this client, or any of its clones, that deadlocks.
+    /// To act on events with the client, hand them over to another thread,
+    /// say over a channel. Reading a [ReadHandle] from a listener is fine.
+    pub fn add_listener<F>(&mut self, listener: F) -> Result<&mut Self>
+    where
+        F: 'static + Fn(&Event) + Send,
+    {
+        {
+            let inner = err_at!(PoisonedLock, self.inner.lock())?;
+            inner
+                .borrow_mut()
+                .endpoints
+                .as_mut()
+                .unwrap()
+                .add_listener(Box::new(listener));
+        }
+        Ok(self)
+    }
+
+    /// Register a post-processing function, like mixing with local
+    /// entropy or re-hashing with a domain tag, that returns new
+    /// randomness for a round. Post-processors are applied, in the order
+    /// of registration, to every round returned by [Client::get],
+    /// [Client::wait_for_round] and watch, after the round is verified.
+    ///
+    /// Returned rounds carry the original round and signature, and their
+    /// randomness is no longer the hash of signature, so they cannot be
+    /// verified again. Check_point, pinned round and aggregates are not
+    /// post-processed. Post-processors are called with the client locked,
+    /// and must not call back into the client.
+    pub fn add_post_processor<F>(&mut self, post: F) -> Result<&mut Self>
+    where
+        F: 'static + Fn(&Random) -> Result<Randomness> + Send,
+    {
+        {
+            let inner = err_at!(PoisonedLock, self.inner.lock())?;
+            inner.borrow_mut().post.push(Box::new(post));
         }
         Ok(self)
     }
 
+    fn post_process(&self, mut r: Random) -> Result<Random> {
+        let inner = err_at!(PoisonedLock, self.inner.lock())?;
+        for post in inner.borrow().post.iter() {
+            r.randomness = post(&r)?;
+        }
+        Ok(r)
+    }
+
     /// Return the hash-info from drand-group. This call is meaningful
     /// only after the [boot] method is called on this client.
     pub fn to_info(&self) -> Result<Info> {
@@ -71,17 +251,160 @@ impl Client {
         Ok(info)
     }
 
+    /// Check every endpoint for reachability, agreement on chain info,
+    /// freshness of the latest round and latency, useful as a configuration
+    /// check before deployment. Chain info is checked against the supplied
+    /// [Config::chain_hash] and client's info, if any, else against the
+    /// first reachable endpoint. Client's state, including latency stats
+    /// and circuits of endpoints, is not modified.
+    ///
+    /// [Config::chain_hash]: crate::Config::chain_hash
+    pub fn validate_endpoints(&self) -> Result<Vec<EndpointCheck>> {
+        use futures::executor::block_on;
+
+        let fut = async {
+            let inner = err_at!(PoisonedLock, self.inner.lock())?;
+            let inner = inner.borrow();
+            let checks = inner.endpoints.as_ref().unwrap().validate_endpoints().await;
+            Ok::<Vec<EndpointCheck>, Error>(checks)
+        };
+        block_on(fut)
+    }
+
+    /// Return HTTP cache headers from the most recent response for the
+    /// latest round, if supplied by the relay.
+    pub fn to_cache_headers(&self) -> Result<Option<CacheHeaders>> {
+        let inner = err_at!(PoisonedLock, self.inner.lock())?;
+        let headers = inner.borrow().endpoints.as_ref().unwrap().to_cache_headers();
+        Ok(headers.map(|(_, headers)| headers))
+    }
+
+    /// Return latency, timing breakdown and relay identification for each
+    /// endpoint, in the order they were added.
+    pub fn to_endpoint_stats(&self) -> Result<Vec<EndpointStats>> {
+        let inner = err_at!(PoisonedLock, self.inner.lock())?;
+        let stats = inner.borrow().endpoints.as_ref().unwrap().to_endpoint_stats();
+        Ok(stats)
+    }
+
+    /// Return requests sent, bytes downloaded and rounds verified by this
+    /// client, across all its endpoints, since it was created. Refer
+    /// [Config::request_budget] to limit requests.
+    ///
+    /// [Config::request_budget]: crate::Config::request_budget
+    pub fn to_usage(&self) -> Result<Usage> {
+        let inner = err_at!(PoisonedLock, self.inner.lock())?;
+        let usage = inner.borrow().endpoints.as_ref().unwrap().to_usage();
+        Ok(usage)
+    }
+
+    // watch rounds after the current round.
+    fn to_watch(&mut self) -> Result<Watch<'_>> {
+        let info = self.to_info()?;
+        let next = info.round_at(self.clock.now())? + 1;
+        Ok(Watch {
+            clock: Arc::clone(&self.clock),
+            backoff: self.backoff.clone(),
+            early: self.early_tolerance,
+            late: self.late_tolerance,
+            client: self,
+            info,
+            next,
+            stalled: None,
+            failures: 0,
+            pause: None,
+        })
+    }
+
+    fn check_stall(&self) -> Result<()> {
+        let inner = err_at!(PoisonedLock, self.inner.lock())?;
+        let res = inner.borrow_mut().endpoints.as_mut().unwrap().check_stall();
+        res
+    }
+
+    fn emit(&self, event: Event) -> Result<()> {
+        let inner = err_at!(PoisonedLock, self.inner.lock())?;
+        inner.borrow().endpoints.as_ref().unwrap().emit(event);
+        Ok(())
+    }
+
+    // remaining time, before relays refresh their cached latest round.
+    fn to_fresh_for(&self) -> Result<Option<time::Duration>> {
+        let inner = err_at!(PoisonedLock, self.inner.lock())?;
+        let headers = inner.borrow().endpoints.as_ref().unwrap().to_cache_headers();
+        let fresh_for = headers.and_then(|(at, headers)| {
+            let fresh_for = headers.to_fresh_for()?;
+            fresh_for.checked_sub(at.elapsed())
+        });
+        Ok(fresh_for)
+    }
+
+    /// Pin `round`, so that requests for the latest round, via `get(None)`,
+    /// return the pinned round, and watch yields the pinned round once and
+    /// ends. Useful to reproduce a past application run, like re-executing
+    /// a draw, against the exact historical beacon. Pinned round is
+    /// fetched and verified with the chain's public key, once, and shared
+    /// by all clones of this client.
+    pub fn pin_round(&mut self, round: u128) -> Result<Random> {
+        let r = self.get_raw(Some(round))?;
+        let info = self.to_info()?;
+        let res = verify_chain(&info.public_key, &r.previous_signature, &r);
+        self.record_incident(res, Some(round))?;
+
+        let inner = err_at!(PoisonedLock, self.inner.lock())?;
+        inner.borrow_mut().pinned = Some(r.clone());
+        Ok(r)
+    }
+
+    /// Unpin the pinned round, returning it, refer [Client::pin_round].
+    pub fn unpin_round(&mut self) -> Result<Option<Random>> {
+        let inner = err_at!(PoisonedLock, self.inner.lock())?;
+        let pinned = inner.borrow_mut().pinned.take();
+        Ok(pinned)
+    }
+
+    /// Return the pinned round, if any, refer [Client::pin_round].
+    pub fn to_pinned(&self) -> Result<Option<Random>> {
+        let inner = err_at!(PoisonedLock, self.inner.lock())?;
+        let pinned = inner.borrow().pinned.clone();
+        Ok(pinned)
+    }
+
     /// Return back the client's name.
     pub fn to_name(&self) -> String {
         self.name.clone()
     }
 
+    /// Return the verified check_point, if any.
+    pub fn to_check_point(&self) -> Result<Option<Random>> {
+        let inner = err_at!(PoisonedLock, self.inner.lock())?;
+        let check_point = inner.borrow().endpoints.as_ref().unwrap().to_check_point();
+        Ok(check_point)
+    }
+
+    /// Return the incident that poisoned this client, if any, refer
+    /// [Config::fail_fast].
+    pub fn to_incident(&self) -> Result<Option<Incident>> {
+        let inner = err_at!(PoisonedLock, self.inner.lock())?;
+        let incident = inner.borrow().incident.clone();
+        Ok(incident)
+    }
+
+    /// Clear the incident that poisoned this client, returning it, so that
+    /// client can be used again. Refer [Config::fail_fast].
+    pub fn clear_incident(&mut self) -> Result<Option<Incident>> {
+        let inner = err_at!(PoisonedLock, self.inner.lock())?;
+        let incident = inner.borrow_mut().incident.take();
+        Ok(incident)
+    }
+
     /// Boot a client. Will verify the endpoint's hash-info and if
     /// configured verify the chain of randomness from root-of-trust or
     /// previous-check-point to latest randomness.
     pub fn boot(&mut self, chain_hash: Option<Vec<u8>>) -> Result<()> {
         use futures::executor::block_on;
 
+        self.check_incident()?;
         let fut = async {
             let inner = err_at!(PoisonedLock, self.inner.lock())?;
             inner
@@ -93,13 +416,214 @@ impl Client {
                 .await?;
             Ok::<(), Error>(())
         };
-        block_on(fut)
+        let res = self.record_incident(block_on(fut), None);
+        self.set_ready(res.is_ok());
+        res
+    }
+
+    /// Estimate the cost of [Client::boot], before starting a determinism
+    /// catch-up. Fetches chain info and latest round from the endpoint
+    /// used for catching up, no other rounds are fetched or verified.
+    pub fn plan_boot(&mut self, chain_hash: Option<Vec<u8>>) -> Result<BootPlan> {
+        use futures::executor::block_on;
+
+        self.check_incident()?;
+        let fut = async {
+            let inner = err_at!(PoisonedLock, self.inner.lock())?;
+            let plan = inner
+                .borrow_mut()
+                .endpoints
+                .as_mut()
+                .unwrap()
+                .plan_boot(chain_hash)
+                .await?;
+            Ok::<BootPlan, Error>(plan)
+        };
+        self.record_incident(block_on(fut), None)
+    }
+
+    /// Same as [Client::boot], but bounded by `deadline`. If booting with
+    /// the configured security policy does not complete within `deadline`,
+    /// for example when determinism catch-up is too slow, it is abandoned
+    /// and client is booted with [SecurityPolicy::Assumed] instead. Returned
+    /// report tells whether such a downgrade happened.
+    ///
+    /// Booting after downgrade is not bounded by `deadline`, it takes a
+    /// single round-trip to each endpoint. If configured policy is already
+    /// `Assumed`, a Timeout error is returned on deadline.
+    ///
+    /// [SecurityPolicy::Assumed]: crate::SecurityPolicy::Assumed
+    pub fn boot_with_deadline(
+        &mut self,
+        chain_hash: Option<Vec<u8>>,
+        deadline: time::Duration,
+    ) -> Result<BootReport> {
+        use futures::executor::block_on;
+
+        self.check_incident()?;
+        let fut = async {
+            let inner = err_at!(PoisonedLock, self.inner.lock())?;
+            let report = inner
+                .borrow_mut()
+                .endpoints
+                .as_mut()
+                .unwrap()
+                .boot_with_deadline(chain_hash, deadline)
+                .await?;
+            Ok::<BootReport, Error>(report)
+        };
+        let res = self.record_incident(block_on(fut), None);
+        self.set_ready(res.is_ok());
+        res
+    }
+
+    /// Return a future that resolves once the client is booted, that is,
+    /// chain info is validated and check_point is established as per
+    /// configured policy, including any catch-up. Resolves right away if
+    /// client is already booted. Can be awaited from another thread while
+    /// [Client::boot] is in progress, say to gate a service's readiness
+    /// probe. Fails if all handles to the client are dropped before boot.
+    pub fn ready(&self) -> impl Future<Output = Result<()>> {
+        let mut rx = self.ready_rx.clone();
+        async move {
+            loop {
+                if *rx.borrow() {
+                    break Ok(());
+                }
+                match rx.recv().await {
+                    Some(true) => break Ok(()),
+                    Some(false) => (),
+                    None => break err_at!(Fatal, msg: format!("client dropped before boot")),
+                }
+            }
+        }
+    }
+
+    /// Return whether the client is booted, refer [Client::ready].
+    pub fn is_ready(&self) -> bool {
+        *self.ready_rx.borrow()
+    }
+
+    // once booted, client stays ready, a failed reboot does not undo the
+    // check_point established earlier.
+    fn set_ready(&self, booted: bool) {
+        if booted {
+            self.share_info();
+        }
+        if booted && !self.is_ready() {
+            self.ready_tx.broadcast(true).ok();
+        }
+    }
+
+    // share chain info with read handles, after boot.
+    fn share_info(&self) {
+        if let Ok(info) = self.to_info() {
+            self.broadcast.set_info(info);
+        }
+    }
+
+    /// Migrate determinism from a legacy chain, when the network moves to a
+    /// new chain, say with a different scheme, so that applications need
+    /// not discard the guarantees established on the legacy chain.
+    ///
+    /// Legacy `check_point` is validated against `legacy`, an offline
+    /// client over the archived legacy chain. Archived round must match
+    /// the check_point and archived rounds after it must verify, as a
+    /// chain, up to the latest archived round. Then this client is booted
+    /// for the new chain `chain_hash`, as per configured policy, which
+    /// establishes the new check_point. Use SecurityPolicy::Full to verify
+    /// the new chain from its first round.
+    pub fn migrate_check_point(
+        &mut self,
+        legacy: &mut OfflineClient,
+        check_point: &Random,
+        chain_hash: Vec<u8>,
+    ) -> Result<Migration> {
+        let legacy_hash = legacy.to_info()?.hash;
+        if legacy_hash.as_bytes() == chain_hash.as_slice() {
+            err_at!(Invalid, msg: format!("legacy chain {} is not a new chain", legacy_hash))?
+        }
+
+        match legacy.get(Some(check_point.round))? {
+            r if &r == check_point => (),
+            r => {
+                let msg = format!("legacy check_point {} != archived {}", check_point, r);
+                err_at!(NotSecure, msg: msg)?
+            }
+        }
+        let legacy_latest = legacy.get(None)?;
+        for round in (check_point.round + 1)..=legacy_latest.round {
+            legacy.get(Some(round))?;
+        }
+
+        self.boot(Some(chain_hash))?;
+        Ok(Migration {
+            legacy_hash,
+            legacy_check_point: check_point.clone(),
+            legacy_latest,
+            check_point: self.to_check_point()?,
+        })
+    }
+
+    /// Verify `beacon`, received from a third party, say embedded in a
+    /// contract event, against the chain this client trusts, without a
+    /// network call. Beacon is verified with the chain's public key, and
+    /// checked for consistency with the verified check_point: same round
+    /// must match byte-for-byte, next round must be chained to it. Errors
+    /// are returned only when the client itself fails, a bad beacon is
+    /// reported as the verdict.
+    pub fn verify_external(&self, beacon: &Random) -> Result<Verdict> {
+        self.check_incident()?;
+
+        let round = beacon.round;
+        if let Err(err) = beacon.validate() {
+            return Ok(Verdict::Malformed {
+                round,
+                msg: err.to_string(),
+            });
+        }
+
+        let info = self.to_info()?;
+        match verify_chain(&info.public_key, &beacon.previous_signature, beacon) {
+            Ok(()) => (),
+            Err(Error::Verify(_, err)) => return Ok(Verdict::BadSignature(err)),
+            Err(err) => return Err(err),
+        }
+
+        let verdict = match self.to_check_point()? {
+            Some(cp) if cp.round == round && &cp != beacon => Verdict::Conflict {
+                round,
+                check_point: cp.round,
+            },
+            Some(cp)
+                if cp.round + 1 == round
+                    && cp.signature.as_bytes() != beacon.previous_signature.as_slice() =>
+            {
+                Verdict::Conflict {
+                    round,
+                    check_point: cp.round,
+                }
+            }
+            _ => Verdict::Valid { round },
+        };
+        Ok(verdict)
     }
 
-    /// Get requested round of randomness.
+    /// Get requested round of randomness. If a round is pinned, refer
+    /// [Client::pin_round], it is returned as the latest round.
     pub fn get(&mut self, round: Option<u128>) -> Result<Random> {
+        let r = self.get_raw(round)?;
+        self.post_process(r)
+    }
+
+    // same as get, without post-processing.
+    fn get_raw(&mut self, round: Option<u128>) -> Result<Random> {
         use futures::executor::block_on;
 
+        self.check_incident()?;
+        if let (None, Some(r)) = (round, self.to_pinned()?) {
+            return Ok(r);
+        }
         let fut = async {
             let inner = err_at!(PoisonedLock, self.inner.lock())?;
             let r = inner
@@ -111,7 +635,327 @@ impl Client {
                 .await?;
             Ok::<Random, Error>(r)
         };
-        block_on(fut)
+        let res = self.flights.call(round, || block_on(fut));
+        // client boots lazily, if not booted explicitly.
+        if res.is_ok() && !self.broadcast.is_info() {
+            self.share_info();
+        }
+        self.record_incident(res, round)
+    }
+
+    fn check_incident(&self) -> Result<()> {
+        let inner = err_at!(PoisonedLock, self.inner.lock())?;
+        let incident = inner.borrow().incident.clone();
+        match incident {
+            Some(incident) => {
+                let prefix = format!("{}:{}", file!(), line!());
+                Err(Error::PoisonedByIncident(prefix, Box::new(incident)))
+            }
+            None => Ok(()),
+        }
+    }
+
+    // in fail-fast mode, integrity failures poison the client.
+    fn record_incident<T>(&self, res: Result<T>, round: Option<u128>) -> Result<T> {
+        match &res {
+            Err(err @ Error::NotSecure(_, _))
+            | Err(err @ Error::Verify(_, _))
+            | Err(err @ Error::Equivocation(_, _)) => self.poison(round, err.clone())?,
+            _ => (),
+        }
+        res
+    }
+
+    fn poison(&self, round: Option<u128>, error: Error) -> Result<()> {
+        let inner = err_at!(PoisonedLock, self.inner.lock())?;
+        let mut inner = inner.borrow_mut();
+        if self.fail_fast && inner.incident.is_none() {
+            let at = self.clock.now();
+            inner.incident = Some(Incident { at, round, error });
+        }
+        Ok(())
+    }
+}
+
+impl Client {
+    /// Accept a gap of `rounds` in the chain, that are not available with
+    /// any of the endpoints, refer [Error::MissingRounds]. When verifying
+    /// the chain, a missing gap that falls within an accepted gap is
+    /// skipped, and the round after the gap is verified with its own
+    /// signature, instead of the chain of signatures leading to it.
+    ///
+    /// This weakens the chain verification, and must be an explicit choice
+    /// by the application, after verifying that the gap is genuine.
+    pub fn accept_gap(&mut self, rounds: ops::RangeInclusive<u128>) -> Result<&mut Self> {
+        {
+            let inner = err_at!(PoisonedLock, self.inner.lock())?;
+            inner
+                .borrow_mut()
+                .endpoints
+                .as_mut()
+                .unwrap()
+                .accept_gap(rounds);
+        }
+        Ok(self)
+    }
+
+    /// Re-fetch rounds `from` till `till`, both inclusive, and verify them
+    /// with the chain's public key, irrespective of the current
+    /// check_point. Useful for spot audits of relays and archives. Rounds
+    /// that fail verification are listed in the returned report, and also
+    /// emitted as [Event::VerificationFailure].
+    pub fn reverify(&mut self, from: u128, till: u128) -> Result<ReverifyReport> {
+        use futures::executor::block_on;
+
+        self.check_incident()?;
+        let fut = async {
+            let inner = err_at!(PoisonedLock, self.inner.lock())?;
+            let report = inner
+                .borrow_mut()
+                .endpoints
+                .as_mut()
+                .unwrap()
+                .reverify(from, till)
+                .await?;
+            Ok::<ReverifyReport, Error>(report)
+        };
+        let report = self.record_incident(block_on(fut), None)?;
+        if let Some((round, msg)) = report.failures.first() {
+            let prefix = format!("{}:{}", file!(), line!());
+            self.poison(Some(*round), Error::NotSecure(prefix, msg.clone()))?;
+        }
+        Ok(report)
+    }
+
+    /// Same as watch, but yield one [Aggregate] for every `n` rounds.
+    /// Windows are aligned to round numbers, `k*n+1..=(k+1)*n`, so that
+    /// all applications sampling with the same `n` see the same
+    /// aggregates. First window is the one holding the next round, its
+    /// earlier rounds are fetched from the endpoints.
+    pub fn watch_aggregated(
+        &mut self,
+        n: u128,
+    ) -> Result<Box<dyn Iterator<Item = Result<Aggregate>> + '_>> {
+        if n == 0 {
+            err_at!(Invalid, msg: format!("aggregate over zero rounds"))?
+        }
+        let watch = self.to_watch()?;
+        Ok(Box::new(WatchAggregated { watch, n }))
+    }
+
+    /// Wait for `round` to be generated by the drand-group, fetch it with
+    /// retries, and verify it as per configured policy. Return a Timeout
+    /// error if the round could not be fetched within `timeout`, as
+    /// measured by the configured clock.
+    pub fn wait_for_round(&mut self, round: u128, timeout: time::Duration) -> Result<Random> {
+        let info = self.to_info()?;
+        let clock = Arc::clone(&self.clock);
+        let deadline = clock.now().checked_add(timeout);
+        let remaining = || match deadline {
+            Some(deadline) => deadline.duration_since(clock.now()).unwrap_or_default(),
+            None => timeout,
+        };
+
+        let at = info.round_time(round)?;
+        if let Ok(dur) = at.duration_since(self.clock.now()) {
+            if dur > remaining() {
+                let msg = format!("round {} expected after {:?}", round, dur);
+                err_at!(Timeout, msg: msg)?
+            }
+            self.clock.sleep(dur)
+        }
+
+        let backoff = self.backoff.clone();
+        let mut retry = Retry::new(&backoff, clock.as_ref());
+        loop {
+            match self.get_raw(Some(round)) {
+                Ok(r) => break self.post_process(r),
+                Err(err @ Error::NotSecure(_, _)) | Err(err @ Error::Verify(_, _)) => {
+                    break Err(err)
+                }
+                Err(err) => match retry.to_delay(&err, info.period) {
+                    delay if delay >= remaining() => {
+                        err_at!(Timeout, msg: format!("round {}, {}", round, err))?
+                    }
+                    delay => retry.sleep(delay),
+                },
+            }
+        }
+    }
+}
+
+impl DrandClient for Client {
+    fn to_info(&self) -> Result<Info> {
+        Client::to_info(self)
+    }
+
+    fn round_at(&self, t: time::SystemTime) -> Result<u128> {
+        Client::to_info(self)?.round_at(t)
+    }
+
+    fn get(&mut self, round: Option<u128>) -> Result<Random> {
+        Client::get(self, round)
+    }
+
+    fn watch(&mut self) -> Result<Box<dyn Iterator<Item = Result<Random>> + '_>> {
+        match self.to_pinned()? {
+            Some(r) => Ok(Box::new(iter::once(self.post_process(r)))),
+            None => Ok(Box::new(self.to_watch()?)),
+        }
+    }
+}
+
+// Iterator blocks until the next round is generated by the drand-group.
+struct Watch<'a> {
+    client: &'a mut Client,
+    clock: Arc<dyn Clock>,
+    backoff: Backoff,
+    early: time::Duration,
+    late: Option<time::Duration>,
+    info: Info,
+    next: u128,
+    // latest round after which a stall was yielded.
+    stalled: Option<u128>,
+    // consecutive failures yielded as errors, and the delay before the
+    // next attempt, so that consumers retrying on errors back off.
+    failures: u32,
+    pause: Option<time::Duration>,
+}
+
+impl<'a> Watch<'a> {
+    // block until `round` is generated, and fetch it. Return MissingRounds
+    // error if round could not be fetched within the late tolerance.
+    fn fetch(&mut self, round: u128) -> Result<Random> {
+        let at = self.info.round_time(round)?;
+        match at.checked_sub(self.early) {
+            Some(early) if early < at => {
+                if let Ok(dur) = early.duration_since(self.clock.now()) {
+                    self.clock.sleep(dur)
+                }
+            }
+            _ => self.sleep_until(at),
+        }
+        if let Some(pause) = self.pause.take() {
+            self.clock.sleep(pause)
+        }
+        let deadline = self.late.and_then(|late| at.checked_add(late));
+
+        let mut retry = Retry::new(&self.backoff, self.clock.as_ref());
+        loop {
+            let err = match self.client.get_raw(Some(round)) {
+                Ok(r) => {
+                    self.failures = 0;
+                    break Ok(r);
+                }
+                Err(err) => err,
+            };
+            // yield a stall once, and keep waiting for the round.
+            match self.client.check_stall() {
+                Err(Error::ChainStalled(p, last, elapsed)) if self.stalled != Some(last) => {
+                    self.stalled = Some(last);
+                    break Err(Error::ChainStalled(p, last, elapsed));
+                }
+                _ => (),
+            }
+            let now = self.clock.now();
+            match (err, deadline) {
+                // polled ahead of schedule, wait for round's scheduled time.
+                (Error::RoundNotYetAvailable(_, _, _), _) if now < at => self.sleep_until(at),
+                (err @ Error::NotSecure(_, _), _)
+                | (err @ Error::Verify(_, _), _)
+                | (err @ Error::PoisonedByIncident(_, _), _) => break Err(err),
+                // local clock is ahead of the relay.
+                (err @ Error::RoundNotYetAvailable(_, _, _), None) => {
+                    retry.sleep(retry.to_delay(&err, self.info.period))
+                }
+                (err, None) => {
+                    let delay = self.backoff.to_retry_delay(self.failures, &err);
+                    self.failures = self.failures.saturating_add(1);
+                    self.pause = Some(delay);
+                    break Err(err);
+                }
+                (err, Some(deadline)) => match deadline.duration_since(now) {
+                    Ok(remaining) if remaining > time::Duration::default() => {
+                        retry.sleep(retry.to_delay(&err, remaining))
+                    }
+                    _ => {
+                        let msg = err.to_string();
+                        self.client.emit(Event::MissedRound { round, msg })?;
+                        let prefix = format!("{}:{}", file!(), line!());
+                        break Err(Error::MissingRounds(prefix, round..=round));
+                    }
+                },
+            }
+        }
+    }
+
+    fn sleep_until(&self, at: time::SystemTime) {
+        if let Ok(dur) = at.duration_since(self.clock.now()) {
+            self.clock.sleep(dur)
+        }
+        // relays behind CDNs serve the new round only after their cached
+        // response expires.
+        match self.client.to_fresh_for() {
+            Ok(Some(dur)) if dur < self.info.period => self.clock.sleep(dur),
+            _ => (),
+        }
+    }
+}
+
+impl<'a> Iterator for Watch<'a> {
+    type Item = Result<Random>;
+
+    // rounds are yielded strictly in order. Rounds that could not be
+    // fetched are yielded as a single MissingRounds gap, starting from
+    // the next round, and watch continues after the gap.
+    fn next(&mut self) -> Option<Self::Item> {
+        let res = match self.fetch(self.next) {
+            Ok(r) if r.round == self.next => {
+                self.next += 1;
+                self.client.post_process(r)
+            }
+            Ok(r) => {
+                let msg = format!("watch expected round {}, got {}", self.next, r.round);
+                err_at!(Invalid, msg: msg)
+            }
+            Err(Error::MissingRounds(p, rounds)) if *rounds.end() >= self.next => {
+                let gap = self.next..=*rounds.end();
+                self.next = rounds.end().saturating_add(1);
+                Err(Error::MissingRounds(p, gap))
+            }
+            Err(err) => Err(err),
+        };
+        Some(res)
+    }
+}
+
+// Iterator blocks until all rounds of the next window are generated by
+// the drand-group. Windows are aligned to round numbers, `k*n+1..=(k+1)*n`.
+struct WatchAggregated<'a> {
+    watch: Watch<'a>,
+    n: u128,
+}
+
+impl<'a> Iterator for WatchAggregated<'a> {
+    type Item = Result<Aggregate>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let from = ((self.watch.next - 1) / self.n) * self.n + 1;
+        let till = from.saturating_add(self.n - 1);
+        let mut randoms = vec![];
+        for round in from..=till {
+            match self.watch.fetch(round) {
+                Ok(r) => randoms.push(r),
+                Err(err @ Error::MissingRounds(_, _)) => {
+                    // skip forward, to the next window.
+                    self.watch.next = till.saturating_add(1);
+                    return Some(Err(err));
+                }
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        self.watch.next = till.saturating_add(1);
+        Some(Ok(Aggregate::from_randoms(randoms)))
     }
 }
 
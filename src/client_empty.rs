@@ -1,80 +1,125 @@
-#[derive(Clone)]
-struct EmptyClient {
-    public_key: (),
-    period: time::Duration,
-    genesis_time: time::SystemTime,
-    hash: Vec<u8>,
+//! Module implement clients that are not connected to a drand-group.
+
+use std::{iter, path, time};
+
+use crate::{verify, Archive, DrandClient, Error, Info, Random, Result};
+
+/// Client that is not connected to any drand-group. Useful as a
+/// placeholder where a [DrandClient] is expected.
+#[derive(Clone, Default)]
+pub struct NullClient {
+    info: Info,
 }
 
-impl From<EmptyClient> for ClientInfo {
-    fn from(val: EmptyClient) -> Self {
-        ClientInfo {
-            public_key: val.public_key,
-            period: val.period,
-            genesis_time: val.genesis_time,
-            hash: val.hash,
-        }
+impl NullClient {
+    /// Create a null client for chain described by `info`.
+    pub fn new(info: Info) -> NullClient {
+        NullClient { info }
     }
 }
 
-impl Client for EmptyClient {
-    type I = EmptyClient;
-    type R = ClientRound;
+impl DrandClient for NullClient {
+    fn to_info(&self) -> Result<Info> {
+        Ok(self.info.clone())
+    }
 
-    fn new<I: Info>(info: I) -> EmptyClient {
-        EmptyClient {
-            public_key: info.to_public_key(),
-            period: info.to_period(),
-            genesis_time: info.to_genesis_time(),
-            hash: info.to_info(),
-        }
+    fn round_at(&self, t: time::SystemTime) -> Result<u128> {
+        self.info.round_at(t)
     }
 
-    fn to_info(&self) -> Result<Info> {
-        self.info.clone()
+    fn get(&mut self, round: Option<u128>) -> Result<Random> {
+        err_at!(Invalid, msg: format!("null client, round {:?}", round))
     }
 
-    fn round_at(&self, t: time::SystemTime) -> Result<u128> {
-        let next_round = match t.elapsed(self.info.genesis_time) {
-            Ok(dur) => {
-                let dur = dur.as_secs;
-                // gives us the number of periods since genesis
-                // we add +1 since we want the next round
-                // we also add +1 because round 1 starts at genesis time.
-                match dur % self.info.period {
-                    0 => (dur / self.info.period) + 1,
-                    _ => (dur / self.info.period) + 1 + 1,
-                }
-            }
-            Err(_) => 1,
-        };
+    fn watch(&mut self) -> Result<Box<dyn Iterator<Item = Result<Random>> + '_>> {
+        Ok(Box::new(vec![].into_iter()))
+    }
+}
+
+/// Client that serves randomness from a local [Archive], without any
+/// network access. Every round served is verified with chain's `info`.
+///
+/// Useful for air-gapped verification and deterministic tests.
+#[derive(Clone)]
+pub struct OfflineClient {
+    info: Info,
+    archive: Archive,
+}
 
-        Ok(next_round)
+impl OfflineClient {
+    /// Create an offline client for chain described by `info`, serving
+    /// randomness from `archive`.
+    pub fn new(info: Info, archive: Archive) -> OfflineClient {
+        OfflineClient { info, archive }
     }
 
-    fn get_round(&self, round: u128) -> Result<u128> {
-        Err(Error::EmptyClient)
+    /// Create an offline client for chain described by `info`, serving
+    /// randomness from archive `file`.
+    pub fn from_file<P: AsRef<path::Path>>(info: Info, file: P) -> Result<OfflineClient> {
+        Ok(OfflineClient::new(info, Archive::from_file(file)?))
     }
 
-    fn watch_rounds(&self) -> Result<Box<dyn Iterator<Item = Result<Self::R>>>> {
-        Ok(Box::new(vec![].into_iter()))
+    /// Return a reference to the underlying archive.
+    pub fn as_archive(&self) -> &Archive {
+        &self.archive
+    }
+
+    fn verify(&self, r: &Random) -> Result<Random> {
+        // if the previous round is archived, verify the chain as well.
+        let prev_sign = match r.round {
+            0 => err_at!(Invalid, msg: format!("round 0"))?,
+            1 => self.info.group_hash.as_slice(),
+            round => match self.archive.get(round - 1) {
+                Some(prev) => prev.signature.as_bytes(),
+                None => r.previous_signature.as_slice(),
+            },
+        };
+        let pk = &self.info.public_key;
+        verify::verify_chain(pk, prev_sign, r)?;
+        Ok(r.clone())
     }
 }
 
-impl Info for EmptyClient {
-    fn to_public_key(&self) -> () {
-        self.public_key.clone()
+impl DrandClient for OfflineClient {
+    fn to_info(&self) -> Result<Info> {
+        Ok(self.info.clone())
     }
 
-    fn to_period(&self) -> time::Duration {
-        self.period.clone()
+    fn round_at(&self, t: time::SystemTime) -> Result<u128> {
+        self.info.round_at(t)
     }
 
-    fn to_genesis_time(&self) -> time::SystemTime {
-        self.genesis_time.clone()
+    fn get(&mut self, round: Option<u128>) -> Result<Random> {
+        let r = match round {
+            Some(round) => self.archive.get(round),
+            None => self.archive.to_latest(),
+        };
+        match r {
+            Some(r) => self.verify(r),
+            None => err_at!(Invalid, msg: format!("round {:?} not archived", round)),
+        }
     }
 
-    fn as_hash(&self) -> &[u8] {
-        self.hash.as_slice()
+    /// Yield archived rounds in order, rounds missing between archived
+    /// rounds are yielded as [Error::MissingRounds] gaps.
+    fn watch(&mut self) -> Result<Box<dyn Iterator<Item = Result<Random>> + '_>> {
+        let this: &Self = self;
+        let mut next: Option<u128> = None;
+        let iter = this.archive.iter().flat_map(move |r| {
+            let gap = match next {
+                Some(n) if n < r.round => {
+                    let prefix = format!("{}:{}", file!(), line!());
+                    Some(Err(Error::MissingRounds(prefix, n..=(r.round - 1))))
+                }
+                _ => None,
+            };
+            next = Some(r.round + 1);
+            gap.into_iter().chain(iter::once(this.verify(r)))
+        });
+        Ok(Box::new(iter))
     }
 }
+
+#[cfg(test)]
+#[path = "client_empty_test.rs"]
+mod client_empty_test;
@@ -0,0 +1,75 @@
+use crate::fixtures::mainnet;
+
+use super::*;
+
+#[test]
+fn test_null_client() {
+    let info = Info {
+        period: time::Duration::from_secs(30),
+        genesis_time: time::UNIX_EPOCH + time::Duration::from_secs(1595431050),
+        ..Info::default()
+    };
+    let mut client: Box<dyn DrandClient> = Box::new(NullClient::new(info.clone()));
+
+    assert_eq!(client.to_info().unwrap(), info);
+    assert!(client.get(None).is_err());
+    assert!(client.get(Some(1)).is_err());
+    assert_eq!(client.watch().unwrap().count(), 0);
+
+    let t = info.genesis_time - time::Duration::from_secs(1);
+    assert_eq!(client.round_at(t).unwrap(), 0);
+    assert_eq!(client.round_at(info.genesis_time).unwrap(), 1);
+    let t = info.genesis_time + time::Duration::from_secs(29);
+    assert_eq!(client.round_at(t).unwrap(), 1);
+    let t = info.genesis_time + time::Duration::from_secs(30);
+    assert_eq!(client.round_at(t).unwrap(), 2);
+
+    assert_eq!(info.round_time(1).unwrap(), info.genesis_time);
+    assert_eq!(
+        info.round_time(3).unwrap(),
+        info.genesis_time + time::Duration::from_secs(60)
+    );
+
+    assert!(NullClient::default().round_at(t).is_err());
+}
+
+#[test]
+fn test_offline_client() {
+    let (info, r1) = mainnet();
+
+    let archive: Archive = vec![r1.clone()].into_iter().collect();
+    let mut client = OfflineClient::new(info.clone(), archive);
+    assert_eq!(client.get(Some(1)).unwrap(), r1);
+    assert_eq!(client.get(None).unwrap(), r1);
+    assert!(client.get(Some(2)).is_err());
+    assert_eq!(client.watch().unwrap().map(|r| r.unwrap()).collect::<Vec<Random>>(), vec![r1.clone()]);
+
+    // tampered signature shall fail verification.
+    let mut bad = r1.clone();
+    bad.signature.0[10] ^= 0xFF;
+    let archive: Archive = vec![bad].into_iter().collect();
+    let mut client = OfflineClient::new(info.clone(), archive);
+    assert!(client.get(Some(1)).is_err());
+
+    // gaps in the archive are yielded as missing rounds.
+    let r4 = Random {
+        round: 4,
+        ..r1.clone()
+    };
+    let archive: Archive = vec![r1.clone(), r4].into_iter().collect();
+    let mut client = OfflineClient::new(info.clone(), archive);
+    let items: Vec<Result<Random>> = client.watch().unwrap().collect();
+    assert_eq!(items.len(), 3);
+    assert_eq!(items[0].as_ref().ok(), Some(&r1));
+    match &items[1] {
+        Err(Error::MissingRounds(_, rounds)) => assert_eq!(*rounds, 2..=3),
+        item => panic!("unexpected {:?}", item.as_ref().map(|r| r.round)),
+    }
+    // round 4 carries round-1's signature.
+    assert!(items[2].is_err());
+
+    let r0 = Random { round: 0, ..r1 };
+    let archive: Archive = vec![r0].into_iter().collect();
+    let mut client = OfflineClient::new(info, archive);
+    assert!(client.get(Some(0)).is_err());
+}
@@ -1,3 +1,7 @@
+use std::convert::TryFrom;
+
+use crate::{ChainHash, CircuitState, Randomness, Signature};
+
 use super::*;
 
 #[test]
@@ -10,6 +14,169 @@ fn test_client_empty_endpoint() {
     assert!(client.get(None).is_err());
 }
 
+#[test]
+fn test_client_instant_boot() {
+    let info = Info {
+        hash: ChainHash([1; 32]),
+        ..Info::default()
+    };
+    let check_point = Random {
+        round: 1,
+        randomness: Randomness::default(),
+        signature: Signature::default(),
+        previous_signature: vec![],
+    };
+    let mut config = Config::default();
+    config
+        .set_info(Some(info.clone()))
+        .set_check_point(Some(check_point));
+    let mut client = Client::from_config("test", config);
+
+    assert!(client.boot(Some(vec![1; 31])).is_err());
+    assert!(client.boot(Some(vec![1; 32])).is_ok());
+    assert!(client.boot(None).is_ok());
+    assert_eq!(client.to_info().unwrap(), info);
+    // lazy validation fails without endpoints.
+    assert!(client.get(None).is_err());
+}
+
+#[test]
+fn test_client_ready() {
+    use futures::executor::block_on;
+
+    let mut config = Config::default();
+    config
+        .set_info(Some(Info {
+            hash: ChainHash([1; 32]),
+            ..Info::default()
+        }))
+        .set_check_point(Some(Random {
+            round: 1,
+            randomness: Randomness::default(),
+            signature: Signature::default(),
+            previous_signature: vec![],
+        }));
+    let mut client = Client::from_config("test", config);
+    let ready = {
+        let client = client.clone();
+        std::thread::spawn(move || block_on(client.ready()))
+    };
+
+    assert!(!client.is_ready());
+    assert!(client.boot(Some(vec![2; 32])).is_err());
+    assert!(!client.is_ready());
+    assert!(client.boot(None).is_ok());
+    assert!(client.is_ready());
+    assert!(ready.join().unwrap().is_ok());
+    // resolves right away once booted.
+    assert!(block_on(client.ready()).is_ok());
+
+    // never booted.
+    let client = Client::from_config("test", Config::default());
+    let ready = client.ready();
+    std::mem::drop(client);
+    assert!(block_on(ready).is_err());
+}
+
+#[test]
+fn test_client_boot_with_deadline() {
+    use crate::SecurityPolicy;
+
+    let deadline = time::Duration::from_secs(1);
+
+    let mut client = Client::from_config("test", Config::default());
+    assert!(client.boot_with_deadline(None, deadline).is_err());
+
+    let info = Info {
+        hash: ChainHash([1; 32]),
+        ..Info::default()
+    };
+    let check_point = Random {
+        round: 1,
+        randomness: Randomness::default(),
+        signature: Signature::default(),
+        previous_signature: vec![],
+    };
+    let mut config = Config::default();
+    config
+        .set_info(Some(info))
+        .set_check_point(Some(check_point))
+        .set_determinism(true);
+    let policy = config.policy.clone();
+    let mut client = Client::from_config("test", config);
+
+    let report = client.boot_with_deadline(None, deadline).unwrap();
+    assert!(!report.is_degraded());
+    assert_eq!(report.requested, policy);
+    assert_eq!(report.policy, policy);
+    assert_eq!(report.check_point, Some(1));
+    assert!(report.elapsed < deadline);
+    assert_ne!(policy, SecurityPolicy::Assumed);
+}
+
+#[test]
+fn test_client_boot_with_deadline_downgrade() {
+    use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+
+    use crate::{
+        fixtures::{info_reply, mainnet_round1, round_reply, MockRelay, Reply},
+        SecurityPolicy,
+    };
+
+    // first fetch of round 1, for determinism, outlasts the deadline.
+    let slow = AtomicBool::new(true);
+    let relay = MockRelay::start(move |path| match path {
+        "/info" => info_reply(),
+        "/public/latest" => round_reply(&mainnet_round1()),
+        "/public/1" => {
+            if slow.swap(false, SeqCst) {
+                std::thread::sleep(time::Duration::from_secs(2));
+            }
+            round_reply(&mainnet_round1())
+        }
+        _ => Reply::new(404, ""),
+    });
+    let endpoints = vec![Endpoint::Http {
+        url: relay.to_url(),
+        category: Category::Origin,
+    }];
+    let deadline = time::Duration::from_millis(500);
+    let rt = tokio::runtime::Runtime::new().unwrap();
+
+    let mut config = Config::default();
+    config
+        .set_endpoints(endpoints)
+        .set_determinism(true)
+        .set_secure(true);
+    let mut client = Client::from_config("test", config);
+    let report = rt.enter(|| client.boot_with_deadline(None, deadline)).unwrap();
+    assert!(report.is_degraded());
+    assert_eq!(report.requested, SecurityPolicy::Full);
+    assert_eq!(report.policy, SecurityPolicy::Assumed);
+    assert_eq!(report.check_point, Some(1));
+    assert!(client.is_ready());
+
+    // already Assumed, nothing to downgrade to.
+    let relay = MockRelay::start(|path| {
+        std::thread::sleep(time::Duration::from_secs(2));
+        match path {
+            "/info" => info_reply(),
+            _ => Reply::new(404, ""),
+        }
+    });
+    let mut config = Config::default();
+    config.set_endpoints(vec![Endpoint::Http {
+        url: relay.to_url(),
+        category: Category::Origin,
+    }]);
+    let mut client = Client::from_config("test", config);
+    match rt.enter(|| client.boot_with_deadline(None, deadline)) {
+        Err(Error::Timeout(_, _)) => (),
+        res => panic!("unexpected {:?}", res.map(|r| r.policy)),
+    }
+    assert!(!client.is_ready());
+}
+
 //#[test]
 //fn test_client_1_no_determinism() {
 //    // with rot
@@ -32,3 +199,494 @@ fn test_client_empty_endpoint() {
 //fn test_client_1_continued_determinism() {
 //    todo!()
 //}
+
+#[test]
+fn test_client_watch_aggregated() {
+    let info = Info {
+        period: time::Duration::from_secs(30),
+        ..Info::default()
+    };
+    let mut config = Config::default();
+    config.set_info(Some(info));
+    let mut client = Client::from_config("test", config);
+
+    assert!(client.watch_aggregated(0).is_err());
+    // without endpoints, first window fails to fetch.
+    let mut iter = client.watch_aggregated(10).unwrap();
+    assert!(iter.next().unwrap().is_err());
+}
+
+#[test]
+fn test_client_fail_fast() {
+    let info = Info {
+        hash: ChainHash([1; 32]),
+        ..Info::default()
+    };
+    let check_point = Random {
+        round: 1,
+        randomness: Randomness::default(),
+        signature: Signature::default(),
+        previous_signature: vec![],
+    };
+    let mut config = Config::default();
+    config
+        .set_info(Some(info))
+        .set_check_point(Some(check_point))
+        .set_fail_fast(true);
+    let mut client = Client::from_config("test", config);
+
+    // chain-info mismatch poisons the client.
+    match client.boot(Some(vec![2; 32])) {
+        Err(Error::NotSecure(_, _)) => (),
+        res => panic!("unexpected {:?}", res),
+    }
+    let incident = client.to_incident().unwrap().unwrap();
+    assert_eq!(incident.round, None);
+    match client.clone().boot(Some(vec![1; 32])) {
+        Err(Error::PoisonedByIncident(_, _)) => (),
+        res => panic!("unexpected {:?}", res),
+    }
+    assert!(matches!(client.get(None), Err(Error::PoisonedByIncident(_, _))));
+
+    assert!(client.clear_incident().unwrap().is_some());
+    assert!(client.to_incident().unwrap().is_none());
+    assert!(client.boot(Some(vec![1; 32])).is_ok());
+}
+
+#[derive(Debug)]
+struct SimClock(std::sync::Mutex<time::SystemTime>);
+
+impl Clock for SimClock {
+    fn now(&self) -> time::SystemTime {
+        *self.0.lock().unwrap()
+    }
+
+    fn sleep(&self, dur: time::Duration) {
+        *self.0.lock().unwrap() += dur
+    }
+}
+
+#[test]
+fn test_client_wait_for_round() {
+    use crate::fixtures::{info_reply, mainnet_info, mainnet_round1, round_reply, MockRelay, Reply};
+
+    let relay = MockRelay::start(|path| match path {
+        "/info" => info_reply(),
+        "/public/latest" | "/public/1" => round_reply(&mainnet_round1()),
+        _ => Reply::new(404, ""),
+    });
+
+    let info = mainnet_info();
+    // round 1 is scheduled at genesis, 10s from now.
+    let start = info.genesis_time - time::Duration::from_secs(10);
+    let clock = Arc::new(SimClock(std::sync::Mutex::new(start)));
+    let mut config = Config::default();
+    config
+        .set_info(Some(info.clone()))
+        .set_clock(Arc::clone(&clock) as Arc<dyn Clock>)
+        .set_endpoints(vec![Endpoint::Http {
+            url: relay.to_url(),
+            category: Category::Origin,
+        }]);
+    let mut client = Client::from_config("test", config);
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let secs = time::Duration::from_secs;
+
+    // round scheduled after the timeout fails right away.
+    match client.wait_for_round(3, secs(30)) {
+        Err(Error::Timeout(_, _)) => (),
+        res => panic!("unexpected {:?}", res),
+    }
+    assert_eq!(clock.now(), start);
+
+    // waits on the clock till the round is generated.
+    let r = rt.enter(|| client.wait_for_round(1, secs(30))).unwrap();
+    assert_eq!(r, mainnet_round1());
+    assert!(clock.now() >= info.genesis_time);
+
+    // round missing with the relay, retried till the timeout.
+    let now = clock.now();
+    match rt.enter(|| client.wait_for_round(2, secs(60))) {
+        Err(Error::Timeout(_, _)) => (),
+        res => panic!("unexpected {:?}", res),
+    }
+    assert!(clock.now() >= info.genesis_time + secs(30));
+    assert!(clock.now() <= now + secs(60));
+}
+
+#[test]
+fn test_client_watch_late_tolerance() {
+    let info = Info {
+        period: time::Duration::from_secs(30),
+        ..Info::default()
+    };
+    let start = time::UNIX_EPOCH + time::Duration::from_secs(100);
+    let clock = Arc::new(SimClock(std::sync::Mutex::new(start)));
+    let mut config = Config::default();
+    config
+        .set_info(Some(info))
+        .set_clock(clock)
+        .set_early_tolerance(time::Duration::from_secs(1))
+        .set_late_tolerance(Some(time::Duration::from_secs(5)));
+    let mut client = Client::from_config("test", config);
+
+    let missed = Arc::new(std::sync::Mutex::new(vec![]));
+    {
+        let missed = Arc::clone(&missed);
+        client
+            .add_listener(move |event| {
+                if let Event::MissedRound { round, .. } = event {
+                    missed.lock().unwrap().push(*round)
+                }
+            })
+            .unwrap();
+    }
+
+    // without endpoints, every round is missed and skipped.
+    let next = client.to_info().unwrap().round_at(start).unwrap() + 1;
+    let mut iter = client.watch().unwrap();
+    for round in next..(next + 2) {
+        match iter.next().unwrap() {
+            Err(Error::MissingRounds(_, rounds)) => assert_eq!(rounds, round..=round),
+            res => panic!("unexpected {:?}", res),
+        }
+    }
+    std::mem::drop(iter);
+    assert_eq!(*missed.lock().unwrap(), vec![next, next + 1]);
+}
+
+#[test]
+fn test_client_pin_round() {
+    let mut client = Client::from_config("test", Config::default());
+    // without endpoints, round cannot be fetched for pinning.
+    assert!(client.pin_round(10).is_err());
+    assert_eq!(client.to_pinned().unwrap(), None);
+
+    let r = Random {
+        round: 10,
+        randomness: Randomness::default(),
+        signature: Signature::default(),
+        previous_signature: vec![],
+    };
+    {
+        let inner = client.inner.lock().unwrap();
+        inner.borrow_mut().pinned = Some(r.clone());
+    }
+
+    assert_eq!(client.clone().get(None).unwrap(), r);
+    assert!(client.get(Some(10)).is_err());
+    let rounds: Vec<Random> = client.watch().unwrap().map(|r| r.unwrap()).collect();
+    assert_eq!(rounds, vec![r.clone()]);
+
+    assert_eq!(client.unpin_round().unwrap(), Some(r));
+    assert_eq!(client.to_pinned().unwrap(), None);
+    assert!(client.get(None).is_err());
+}
+
+#[test]
+fn test_client_post_processor() {
+    use sha2::{Digest, Sha256};
+
+    let mut client = Client::from_config("test", Config::default());
+    let r = Random {
+        round: 10,
+        randomness: Randomness([1; 32]),
+        signature: Signature::default(),
+        previous_signature: vec![],
+    };
+    {
+        let inner = client.inner.lock().unwrap();
+        inner.borrow_mut().pinned = Some(r.clone());
+    }
+
+    // re-hash with a domain tag, then mix with local entropy.
+    client
+        .add_post_processor(|r: &Random| {
+            let mut hasher = Sha256::default();
+            hasher.update(b"my-app");
+            hasher.update(r.randomness.as_bytes());
+            Randomness::try_from(hasher.finalize().as_slice())
+        })
+        .unwrap()
+        .add_post_processor(|r: &Random| {
+            let mut bytes = r.randomness.0;
+            bytes.iter_mut().for_each(|b| *b ^= 0xFF);
+            Ok(Randomness(bytes))
+        })
+        .unwrap();
+
+    let mut expected: [u8; 32] = Sha256::new()
+        .chain(b"my-app")
+        .chain([1; 32])
+        .finalize()
+        .into();
+    expected.iter_mut().for_each(|b| *b ^= 0xFF);
+
+    let got = client.get(None).unwrap();
+    assert_eq!(got.round, r.round);
+    assert_eq!(got.signature, r.signature);
+    assert_eq!(got.randomness, Randomness(expected));
+    let rounds: Vec<Random> = client.watch().unwrap().map(|r| r.unwrap()).collect();
+    assert_eq!(rounds, vec![got]);
+    // pinned round is not post-processed.
+    assert_eq!(client.to_pinned().unwrap(), Some(r));
+
+    client
+        .add_post_processor(|_: &Random| err_at!(Invalid, msg: format!("policy")))
+        .unwrap();
+    assert!(client.get(None).is_err());
+}
+
+#[test]
+fn test_client_migrate_check_point() {
+    use crate::{fixtures::mainnet, Archive, OfflineClient};
+
+    let (info, r1) = mainnet();
+    let legacy_info = Info {
+        public_key: info.public_key,
+        hash: info.hash,
+        group_hash: info.group_hash,
+        ..Info::default()
+    };
+    let archive: Archive = vec![r1.clone()].into_iter().collect();
+    let mut legacy = OfflineClient::new(legacy_info.clone(), archive);
+
+    // new chain, boots instantly with supplied info and check_point.
+    let info = Info {
+        hash: ChainHash([1; 32]),
+        ..Info::default()
+    };
+    let check_point = Random {
+        round: 1,
+        randomness: Randomness::default(),
+        signature: Signature::default(),
+        previous_signature: vec![],
+    };
+    let mut config = Config::default();
+    config
+        .set_info(Some(info))
+        .set_check_point(Some(check_point.clone()));
+    let mut client = Client::from_config("test", config);
+
+    // legacy chain is not a new chain.
+    let hash = legacy_info.hash.as_bytes().to_vec();
+    assert!(client.migrate_check_point(&mut legacy, &r1, hash).is_err());
+    // legacy check_point does not match the archive.
+    let mut bad = r1.clone();
+    bad.previous_signature = vec![0; 32];
+    match client.migrate_check_point(&mut legacy, &bad, vec![1; 32]) {
+        Err(Error::NotSecure(_, _)) => (),
+        res => panic!("unexpected {:?}", res),
+    }
+
+    let migration = client.migrate_check_point(&mut legacy, &r1, vec![1; 32]).unwrap();
+    assert_eq!(migration.legacy_hash, legacy_info.hash);
+    assert_eq!(migration.legacy_check_point, r1);
+    assert_eq!(migration.legacy_latest, r1);
+    assert_eq!(migration.check_point, Some(check_point));
+}
+
+#[test]
+fn test_client_verify_external() {
+    use crate::{fixtures::mainnet, VerifyError};
+
+    let (info, r1) = mainnet();
+    let info = Info {
+        public_key: info.public_key,
+        group_hash: info.group_hash,
+        ..Info::default()
+    };
+
+    let mut config = Config::default();
+    config.set_info(Some(info.clone()));
+    let client = Client::from_config("test", config);
+    assert_eq!(client.verify_external(&r1).unwrap(), Verdict::Valid { round: 1 });
+
+    let mut bad = r1.clone();
+    bad.randomness.0[0] ^= 0xFF;
+    let verdict = client.verify_external(&bad).unwrap();
+    assert!(matches!(verdict, Verdict::Malformed { round: 1, .. }));
+    assert!(!verdict.is_valid());
+
+    let bad = Random {
+        round: 2,
+        ..r1.clone()
+    };
+    match client.verify_external(&bad).unwrap() {
+        Verdict::BadSignature(VerifyError::PairingFailure { round: 2 }) => (),
+        verdict => panic!("unexpected {:?}", verdict),
+    }
+
+    // verified check_point differs from the beacon.
+    let check_point = Random {
+        round: 1,
+        randomness: Randomness::default(),
+        signature: Signature::default(),
+        previous_signature: vec![],
+    };
+    let mut config = Config::default();
+    config.set_info(Some(info)).set_check_point(Some(check_point));
+    let client = Client::from_config("test", config);
+    let verdict = Verdict::Conflict {
+        round: 1,
+        check_point: 1,
+    };
+    assert_eq!(client.verify_external(&r1).unwrap(), verdict);
+}
+
+#[test]
+fn test_client_validate_endpoints() {
+    let mut client = Client::from_config("test", Config::default());
+    assert!(client.validate_endpoints().unwrap().is_empty());
+
+    // nothing listens on port 1.
+    let url = "http://127.0.0.1:1".to_string();
+    let category = Category::Cache;
+    client.add_endpoint(Endpoint::Http { url, category }).unwrap();
+
+    let checks = client.validate_endpoints().unwrap();
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0].url, "http://127.0.0.1:1");
+    assert_eq!(checks[0].category, Category::Cache);
+    assert!(!checks[0].is_ok());
+    assert_eq!(checks[0].info, None);
+    assert_eq!(checks[0].latest, None);
+
+    // failed check is not accounted in endpoint's stats.
+    let stats = client.to_endpoint_stats().unwrap();
+    assert_eq!(stats[0].samples, 0);
+    assert_eq!(stats[0].circuit, CircuitState::Closed);
+}
+
+#[test]
+fn test_client_watch_stalled() {
+    let info = Info {
+        period: time::Duration::from_secs(30),
+        ..Info::default()
+    };
+    // check_point is round 1, scheduled at genesis, 100s ago.
+    let start = time::UNIX_EPOCH + time::Duration::from_secs(100);
+    let clock = Arc::new(SimClock(std::sync::Mutex::new(start)));
+    let mut config = Config::default();
+    config
+        .set_info(Some(info))
+        .set_check_point(Some(Random {
+            round: 1,
+            randomness: Randomness::default(),
+            signature: Signature::default(),
+            previous_signature: vec![],
+        }))
+        .set_clock(clock)
+        .set_stall_periods(Some(2));
+    let mut client = Client::from_config("test", config);
+
+    let stalls = Arc::new(std::sync::Mutex::new(vec![]));
+    {
+        let stalls = Arc::clone(&stalls);
+        client
+            .add_listener(move |event| {
+                if let Event::ChainStalled { round, elapsed } = event {
+                    stalls.lock().unwrap().push((*round, *elapsed))
+                }
+            })
+            .unwrap();
+    }
+
+    let mut iter = client.watch().unwrap();
+    match iter.next().unwrap() {
+        Err(Error::ChainStalled(_, round, elapsed)) => {
+            assert_eq!(round, 1);
+            assert!(elapsed >= time::Duration::from_secs(100));
+        }
+        res => panic!("unexpected {:?}", res.map(|r| r.round)),
+    }
+    // stall is yielded once, watch keeps waiting for the round.
+    match iter.next().unwrap() {
+        Err(Error::ChainStalled(_, _, _)) => panic!("stall yielded twice"),
+        Err(_) => (),
+        Ok(r) => panic!("unexpected {}", r),
+    }
+    std::mem::drop(iter);
+    assert_eq!(stalls.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn test_client_request_budget() {
+    let start = time::UNIX_EPOCH + time::Duration::from_secs(100);
+    let clock = Arc::new(SimClock(std::sync::Mutex::new(start)));
+    let mut config = Config::default();
+    config
+        .set_info(Some(Info::default()))
+        .set_clock(Arc::clone(&clock) as Arc<dyn Clock>)
+        .set_request_budget(Some(0));
+    let mut client = Client::from_config("test", config);
+    assert_eq!(client.to_usage().unwrap(), Usage::default());
+
+    let exceeded = Arc::new(std::sync::Mutex::new(vec![]));
+    {
+        let exceeded = Arc::clone(&exceeded);
+        client
+            .add_listener(move |event| {
+                if let Event::BudgetExceeded { requests, budget } = event {
+                    exceeded.lock().unwrap().push((*requests, *budget))
+                }
+            })
+            .unwrap();
+    }
+
+    for _ in 0..3 {
+        match client.get(Some(5)) {
+            Err(Error::BudgetExceeded(_, budget)) => assert_eq!(budget, 0),
+            res => panic!("unexpected {:?}", res.map(|r| r.round)),
+        }
+    }
+    assert_eq!(*exceeded.lock().unwrap(), vec![(0, 0)]);
+
+    // reported again in the next hour.
+    clock.sleep(time::Duration::from_secs(3600));
+    assert!(client.get(Some(5)).is_err());
+    assert_eq!(exceeded.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn test_client_watch_backoff() {
+    use crate::fixtures::{info_reply, mainnet_info, MockRelay, Reply};
+
+    let relay = MockRelay::start(|path| match path {
+        "/info" => info_reply(),
+        _ => Reply::new(500, ""),
+    });
+
+    let info = mainnet_info();
+    let start = info.genesis_time + time::Duration::from_secs(3000);
+    let clock = Arc::new(SimClock(std::sync::Mutex::new(start)));
+    let mut config = Config::default();
+    config
+        .set_info(Some(info))
+        .set_clock(Arc::clone(&clock) as Arc<dyn Clock>)
+        .set_endpoints(vec![Endpoint::Http {
+            url: relay.to_url(),
+            category: Category::Origin,
+        }])
+        .set_backoff(Backoff {
+            initial: time::Duration::from_secs(1),
+            factor: 2,
+            max: time::Duration::from_secs(30),
+        });
+    let mut client = Client::from_config("test", config);
+
+    // failed attempts are yielded, backing off between them.
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let times = rt.enter(|| {
+        let mut iter = client.watch().unwrap();
+        let mut times = vec![];
+        for _ in 0..3 {
+            assert!(iter.next().unwrap().is_err());
+            times.push(clock.now());
+        }
+        times
+    });
+    let delay = |i: usize| times[i].duration_since(times[i - 1]).unwrap();
+    assert!(delay(1) >= time::Duration::from_secs(1), "{:?}", delay(1));
+    assert!(delay(2) >= time::Duration::from_secs(2), "{:?}", delay(2));
+}
@@ -0,0 +1,32 @@
+//! Module implement source of wall-clock time used by clients.
+
+use std::{fmt, thread, time};
+
+/// Source of wall-clock time, used to schedule rounds, refer
+/// [Config::clock].
+///
+/// Applications can supply their own implementation to simulate time in
+/// tests, or to correct for a known skew in the local clock. Waiting
+/// between rounds, and between retries, goes through [Clock::sleep].
+///
+/// [Config::clock]: crate::Config::clock
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Return the current time.
+    fn now(&self) -> time::SystemTime;
+
+    /// Block the calling thread for `dur`. Default implementation uses
+    /// the thread's sleep, simulated clocks can advance their time instead.
+    fn sleep(&self, dur: time::Duration) {
+        thread::sleep(dur)
+    }
+}
+
+/// Default clock, return the local system time.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> time::SystemTime {
+        time::SystemTime::now()
+    }
+}
@@ -0,0 +1,248 @@
+//! Module implement loading [Config] from a TOML document, refer
+//! [Config::from_path].
+//!
+//! ```toml
+//! chain = "mainnet"               # chain name or chain-hash in hex
+//! policy = "window"               # "assumed", "full" or "window"
+//! window = "1h"
+//! secure = true
+//! max_conns = 4
+//! global_concurrency = 16
+//! cache_historical = true
+//! fail_fast = false
+//! agreement = "prefer-verified"   # "prefer-higher" or "require-match"
+//! state_dir = "/var/lib/drand"
+//! proxy = "http://proxy.local:3128"
+//! max_period = "3s"               # pick chain by period, if no chain
+//! request_budget = 1000           # requests per hour
+//! recompute_randomness = true     # ignore randomness served by relays
+//!
+//! [[endpoints]]
+//! url = "https://api.drand.sh"    # or "drand-api", "cloudflare" ...
+//!
+//! [[endpoints]]
+//! url = "https://relay.example.com"
+//! category = "cache"              # "origin" or "cache", default origin
+//!
+//! [timeouts]
+//! early_tolerance = "500ms"
+//! late_tolerance = "5s"
+//! hedge_after = "200ms"           # or hedge_percentile = 95
+//! request_timeout = "10s"
+//!
+//! [backoff]
+//! initial = "100ms"
+//! factor = 2
+//! max = "30s"
+//!
+//! [breaker]
+//! failures = 5
+//! cool_off = "30s"
+//! successes = 2
+//! ```
+//!
+//! All keys are optional, missing keys take their value from
+//! [Config::default]. Durations are integers suffixed with `ms`, `s`,
+//! `m` or `h`. Unknown keys fail with Invalid error, to catch typos.
+
+use serde::Deserialize;
+
+use std::{path, time};
+
+use crate::{
+    Agreement, Backoff, Breaker, Category, Chain, Config, Endpoint, Hedge, Result, SecurityPolicy,
+};
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigToml {
+    chain: Option<String>,
+    policy: Option<String>,
+    window: Option<String>,
+    secure: Option<bool>,
+    max_conns: Option<usize>,
+    global_concurrency: Option<usize>,
+    cache_historical: Option<bool>,
+    fail_fast: Option<bool>,
+    agreement: Option<String>,
+    state_dir: Option<path::PathBuf>,
+    proxy: Option<String>,
+    max_period: Option<String>,
+    request_budget: Option<u64>,
+    recompute_randomness: Option<bool>,
+    #[serde(default)]
+    endpoints: Vec<EndpointToml>,
+    timeouts: Option<TimeoutsToml>,
+    backoff: Option<BackoffToml>,
+    breaker: Option<BreakerToml>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EndpointToml {
+    url: String,
+    category: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TimeoutsToml {
+    early_tolerance: Option<String>,
+    late_tolerance: Option<String>,
+    hedge_after: Option<String>,
+    hedge_percentile: Option<u8>,
+    request_timeout: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BackoffToml {
+    initial: Option<String>,
+    factor: Option<u32>,
+    max: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BreakerToml {
+    failures: Option<u32>,
+    cool_off: Option<String>,
+    successes: Option<u32>,
+}
+
+// parse `doc` as TOML, and apply it on default configuration.
+pub(crate) fn from_toml(doc: &str) -> Result<Config> {
+    let val: ConfigToml = err_at!(Invalid, toml::from_str(doc), format!("config"))?;
+
+    let mut config = Config::default();
+    if let Some(chain) = val.chain.as_ref() {
+        let chain: Chain = chain.parse()?;
+        config.set_chain_hash(chain.into());
+    }
+    config.policy = match (val.policy.as_deref(), val.window.as_deref()) {
+        (None, None) => config.policy,
+        (Some("assumed"), None) => SecurityPolicy::Assumed,
+        (Some("full"), None) => SecurityPolicy::Full,
+        (Some("window"), Some(w)) | (None, Some(w)) => SecurityPolicy::Window(to_duration(w)?),
+        (Some("window"), None) => err_at!(Invalid, msg: format!("policy window, missing window"))?,
+        (Some(p), _) => err_at!(Invalid, msg: format!("policy {:?}", p))?,
+    };
+    if let Some(agreement) = val.agreement.as_deref() {
+        config.agreement = match agreement {
+            "prefer-higher" => Agreement::PreferHigher,
+            "require-match" => Agreement::RequireMatch,
+            "prefer-verified" => Agreement::PreferVerified,
+            a => err_at!(Invalid, msg: format!("agreement {:?}", a))?,
+        };
+    }
+    config.secure = val.secure.unwrap_or(config.secure);
+    config.max_conns = val.max_conns.unwrap_or(config.max_conns);
+    config.global_concurrency = val.global_concurrency.or(config.global_concurrency);
+    config.cache_historical = val.cache_historical.unwrap_or(config.cache_historical);
+    config.fail_fast = val.fail_fast.unwrap_or(config.fail_fast);
+    config.state_dir = val.state_dir.or(config.state_dir);
+    config.proxy = val.proxy.or(config.proxy);
+    if let Some(max_period) = val.max_period.as_deref() {
+        config.max_period = Some(to_duration(max_period)?);
+    }
+    config.request_budget = val.request_budget.or(config.request_budget);
+    config.recompute_randomness = val.recompute_randomness.unwrap_or(config.recompute_randomness);
+
+    for endp in val.endpoints.into_iter() {
+        config.endpoints.push(to_endpoint(endp)?);
+    }
+
+    if let Some(t) = val.timeouts {
+        if let Some(early) = t.early_tolerance.as_deref() {
+            config.early_tolerance = to_duration(early)?;
+        }
+        if let Some(late) = t.late_tolerance.as_deref() {
+            config.late_tolerance = Some(to_duration(late)?);
+        }
+        config.hedge = match (t.hedge_after.as_deref(), t.hedge_percentile) {
+            (Some(_), Some(_)) => {
+                err_at!(Invalid, msg: format!("both hedge_after and hedge_percentile"))?
+            }
+            (Some(after), None) => Some(Hedge::After(to_duration(after)?)),
+            (None, Some(p)) if (1..=100).contains(&p) => Some(Hedge::Percentile(p)),
+            (None, Some(p)) => err_at!(Invalid, msg: format!("hedge_percentile {}", p))?,
+            (None, None) => config.hedge,
+        };
+        if let Some(timeout) = t.request_timeout.as_deref() {
+            config.request_timeout = Some(to_duration(timeout)?);
+        }
+    }
+
+    if let Some(b) = val.backoff {
+        let mut backoff = Backoff::default();
+        if let Some(initial) = b.initial.as_deref() {
+            backoff.initial = to_duration(initial)?;
+        }
+        backoff.factor = b.factor.unwrap_or(backoff.factor);
+        if let Some(max) = b.max.as_deref() {
+            backoff.max = to_duration(max)?;
+        }
+        config.backoff = backoff;
+    }
+
+    if let Some(b) = val.breaker {
+        let mut breaker = Breaker::default();
+        breaker.failures = b.failures.unwrap_or(breaker.failures);
+        if let Some(cool_off) = b.cool_off.as_deref() {
+            breaker.cool_off = to_duration(cool_off)?;
+        }
+        breaker.successes = b.successes.unwrap_or(breaker.successes);
+        config.breaker = breaker;
+    }
+
+    Ok(config)
+}
+
+fn to_endpoint(endp: EndpointToml) -> Result<Endpoint> {
+    let url = endp.url.trim_end_matches('/');
+    let known = match url {
+        "drand-api" | "https://api.drand.sh" => Some(Endpoint::HttpDrandApi),
+        "drand-api2" | "https://api2.drand.sh" => Some(Endpoint::HttpDrandApi2),
+        "drand-api3" | "https://api3.drand.sh" => Some(Endpoint::HttpDrandApi3),
+        "cloudflare" | "https://drand.cloudflare.com" => Some(Endpoint::HttpCloudflare),
+        _ => None,
+    };
+    match (known, endp.category.as_deref()) {
+        (Some(endp), None) => Ok(endp),
+        _ if !url.starts_with("http://") && !url.starts_with("https://") => {
+            err_at!(Invalid, msg: format!("endpoint url {:?}", url))
+        }
+        (_, category) => {
+            let category = match category {
+                None | Some("origin") => Category::Origin,
+                Some("cache") => Category::Cache,
+                Some(c) => err_at!(Invalid, msg: format!("endpoint category {:?}", c))?,
+            };
+            let url = url.to_string();
+            Ok(Endpoint::Http { url, category })
+        }
+    }
+}
+
+// parse duration like `500ms`, `30s`, `5m`, `1h`.
+fn to_duration(s: &str) -> Result<time::Duration> {
+    let s = s.trim();
+    let n = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (num, unit) = s.split_at(n);
+    let num: u64 = err_at!(Invalid, num.parse(), format!("duration {:?}", s))?;
+    let secs = |m: u64| match num.checked_mul(m) {
+        Some(secs) => Ok(time::Duration::from_secs(secs)),
+        None => err_at!(Invalid, msg: format!("duration {:?}", s)),
+    };
+    match unit.trim() {
+        "ms" => Ok(time::Duration::from_millis(num)),
+        "s" => secs(1),
+        "m" => secs(60),
+        "h" => secs(3600),
+        _ => err_at!(Invalid, msg: format!("duration {:?}, unit ms/s/m/h", s)),
+    }
+}
+
+#[cfg(test)]
+#[path = "conffile_test.rs"]
+mod conffile_test;
@@ -0,0 +1,103 @@
+use super::*;
+
+use crate::MAINNET_CHAIN_HASH;
+
+#[test]
+fn test_from_toml() {
+    let doc = r#"
+        chain = "mainnet"
+        policy = "window"
+        window = "1h"
+        secure = true
+        max_conns = 8
+        cache_historical = false
+        agreement = "prefer-verified"
+        state_dir = "/var/lib/drand"
+        proxy = "http://proxy.local:3128"
+        max_period = "3s"
+        request_budget = 1000
+        recompute_randomness = true
+
+        [[endpoints]]
+        url = "drand-api"
+
+        [[endpoints]]
+        url = "https://relay.example.com/"
+        category = "cache"
+
+        [timeouts]
+        early_tolerance = "500ms"
+        late_tolerance = "5s"
+        hedge_percentile = 95
+        request_timeout = "10s"
+
+        [breaker]
+        failures = 3
+    "#;
+    let config: Config = doc.parse().unwrap();
+
+    let chain_hash = hex::decode(MAINNET_CHAIN_HASH).unwrap();
+    assert_eq!(config.chain_hash, Some(chain_hash));
+    assert_eq!(config.policy, SecurityPolicy::Window(time::Duration::from_secs(3600)));
+    assert!(config.secure);
+    assert_eq!(config.max_conns, 8);
+    assert!(!config.cache_historical);
+    assert_eq!(config.agreement, Agreement::PreferVerified);
+    assert_eq!(config.state_dir, Some(path::PathBuf::from("/var/lib/drand")));
+    assert_eq!(config.proxy.as_deref(), Some("http://proxy.local:3128"));
+    assert_eq!(config.max_period, Some(time::Duration::from_secs(3)));
+    assert_eq!(config.request_timeout, Some(time::Duration::from_secs(10)));
+    assert_eq!(config.request_budget, Some(1000));
+    assert!(config.recompute_randomness);
+
+    assert_eq!(config.endpoints.len(), 2);
+    assert!(matches!(config.endpoints[0], Endpoint::HttpDrandApi));
+    match &config.endpoints[1] {
+        Endpoint::Http { url, category } => {
+            assert_eq!(url, "https://relay.example.com");
+            assert_eq!(*category, Category::Cache);
+        }
+        endp => panic!("unexpected {:?}", endp),
+    }
+
+    assert_eq!(config.early_tolerance, time::Duration::from_millis(500));
+    assert_eq!(config.late_tolerance, Some(time::Duration::from_secs(5)));
+    assert_eq!(config.hedge, Some(Hedge::Percentile(95)));
+    assert_eq!(config.breaker.failures, 3);
+    assert_eq!(config.breaker.cool_off, Breaker::default().cool_off);
+    assert_eq!(config.backoff, Backoff::default());
+
+    // empty document is default configuration.
+    let config: Config = "".parse().unwrap();
+    assert_eq!(config.policy, SecurityPolicy::Assumed);
+    assert!(config.endpoints.is_empty());
+    assert_eq!(config.chain_hash, None);
+    assert!(!config.recompute_randomness);
+
+    let bad = [
+        "polcy = \"full\"",
+        "policy = \"strict\"",
+        "policy = \"window\"",
+        "chain = \"moonnet\"",
+        "[timeouts]\nlate_tolerance = \"5 days\"",
+        "[timeouts]\nhedge_after = \"1s\"\nhedge_percentile = 95",
+        "[[endpoints]]\nurl = \"relay.example.com\"",
+        "[[endpoints]]\nurl = \"https://relay.example.com\"\ncategory = \"edge\"",
+    ];
+    for doc in bad.iter() {
+        assert!(doc.parse::<Config>().is_err(), "{}", doc);
+    }
+}
+
+#[test]
+fn test_to_duration() {
+    assert_eq!(to_duration("250ms").unwrap(), time::Duration::from_millis(250));
+    assert_eq!(to_duration("30s").unwrap(), time::Duration::from_secs(30));
+    assert_eq!(to_duration(" 5m ").unwrap(), time::Duration::from_secs(300));
+    assert_eq!(to_duration("2h").unwrap(), time::Duration::from_secs(7200));
+
+    assert!(to_duration("").is_err());
+    assert!(to_duration("5").is_err());
+    assert!(to_duration("s").is_err());
+    assert!(to_duration("1.5s").is_err());
+}
@@ -1,17 +1,44 @@
+use serde::Deserialize;
 use sha2::{Digest, Sha256};
 
-use std::{error, fmt, result, time};
+use crate::{
+    backoff::Backoff,
+    breaker::Breaker,
+    clock::{Clock, SystemClock},
+    conffile,
+    http::RandomJson,
+    ipfilter::IpFilter,
+    scheme::Scheme,
+    transport::SharedTransport,
+    types::{ChainHash, PublicKey, Randomness, Signature},
+    verify::VerifyError,
+    Category, Endpoint,
+};
+
+use std::{
+    convert::{TryFrom, TryInto},
+    error, fmt, fs, ops, path, result,
+    str::FromStr,
+    sync::Arc,
+    time,
+};
 
 pub const MAX_CONNS: usize = 4;
 
+/// Number of rounds after which catch-up progress is persisted, refer
+/// [CatchUp::Gentle].
+pub const PROGRESS_ROUNDS: u128 = 100;
+
 #[derive(Clone, Debug)]
 /// Configuration parameters for Client.
 pub struct Config {
     /// A previously fetched round serving as a verification checkpoint.
     ///
-    /// * if `determinism` is true and check_point is None, Round-1 acts
+    /// * if `policy` is Full and check_point is None, Round-1 acts
     ///   as the the check_point round.
-    /// * if `determinism` is false, lastest round is assumed as verified
+    /// * if `policy` is Window, and check_point is older than the window,
+    ///   first round of the window acts as the check_point round.
+    /// * if `policy` is Assumed, lastest round is assumed as verified
     ///   round and treated as `check_point`.
     /// * if `secure` is false, every beacon round is assumed as verfied
     ///   round.
@@ -20,10 +47,18 @@ pub struct Config {
     ///
     /// Default: None
     pub check_point: Option<Random>,
-    /// Ensure all rounds from check_point to the latest round is valid
+    /// Previously fetched hash-info of the drand-group. If both `info` and
+    /// `check_point` are supplied, booting the client is instant, without
+    /// any network call, and `info` is validated with the drand-group on
+    /// the first network call.
     ///
-    /// Default: false,
-    pub determinism: bool,
+    /// Default: None
+    pub info: Option<Info>,
+    /// Policy to verify the chain of randomness, from check_point to the
+    /// latest round, while booting the client. Refer [SecurityPolicy].
+    ///
+    /// Default: SecurityPolicy::Assumed,
+    pub policy: SecurityPolicy,
     /// Ensure all future rounds from latest round is verified.
     ///
     /// Default: false
@@ -32,27 +67,257 @@ pub struct Config {
     ///
     /// Default: MAX_CONNS
     pub max_conns: usize,
+    /// Maximum number of concurrent requests allowed across all remotes
+    /// of a client. Caps the number of sockets opened while catching up
+    /// with the latest round using several endpoints.
+    ///
+    /// Default: None, no global limit.
+    pub global_concurrency: Option<usize>,
+    /// Fetch historical rounds from `Category::Cache` endpoints, while the
+    /// latest rounds are fetched from `Category::Origin` endpoints. Falls
+    /// back to any available endpoint when no endpoint of the preferred
+    /// category is available.
+    ///
+    /// Default: true
+    pub cache_historical: bool,
+    /// Hedge requests to reduce tail latency. If configured, request is
+    /// sent to the best endpoint, and only if it does not respond within
+    /// the hedge threshold, the same request is sent to the second best
+    /// endpoint, and whichever responds first is used.
+    ///
+    /// Default: None, request is sent to two best endpoints concurrently.
+    pub hedge: Option<Hedge>,
+    /// Profile for fetching rounds while verifying the chain of rounds,
+    /// from check_point to the latest round.
+    ///
+    /// Default: CatchUp::Eager
+    pub catch_up: CatchUp,
+    /// Policy to pick a response, when two endpoints respond to the same
+    /// request. Refer [Agreement]. Irrespective of the policy, responses
+    /// for the same round are compared byte-for-byte, and a mismatch is
+    /// returned as Equivocation error.
+    ///
+    /// Default: Agreement::PreferHigher
+    pub agreement: Agreement,
+    /// Restrict the IP addresses the client may connect to, refer
+    /// [IpFilter]. Useful in egress restricted environments, and when
+    /// endpoints are configured from user input.
+    ///
+    /// Default: None, all addresses are allowed.
+    pub ip_filter: Option<IpFilter>,
+    /// Source of current time, used to compute the current round and to
+    /// schedule future rounds. Refer [Clock].
+    ///
+    /// Default: SystemClock
+    pub clock: Arc<dyn Clock>,
+    /// Fail-fast mode, for services that must stop issuing results on any
+    /// integrity doubt. Any verification failure, equivocation, or
+    /// chain-info mismatch poisons the client, subsequent calls return
+    /// [Error::PoisonedByIncident] until the incident is cleared with
+    /// [Client::clear_incident].
+    ///
+    /// Default: false
+    ///
+    /// [Client::clear_incident]: crate::Client::clear_incident
+    pub fail_fast: bool,
+    /// Backoff between retries, while waiting for a round, refer [Backoff].
+    ///
+    /// Default: 100ms doubling on every retry, up to 30s.
+    pub backoff: Backoff,
+    /// Circuit breaker applied to every endpoint, refer [Breaker].
+    /// Endpoints are left out of endpoint selection while their circuit
+    /// is open, and emit [Event::Quarantine] when it opens.
+    ///
+    /// Default: open after 5 consecutive failures, probe after 30s, close
+    /// after 2 consecutive successes.
+    ///
+    /// [Event::Quarantine]: crate::Event::Quarantine
+    pub breaker: Breaker,
+    /// Accept rounds up to `early_tolerance` before their scheduled time,
+    /// to tolerate a local clock lagging behind the drand-group. Watch
+    /// starts polling for the next round this much ahead of its scheduled
+    /// time.
+    ///
+    /// Default: zero, rounds are fetched only after their scheduled time.
+    pub early_tolerance: time::Duration,
+    /// Keep retrying a round, while watching, until `late_tolerance` after
+    /// its scheduled time. If the round could not be fetched by then,
+    /// watch emits [Event::MissedRound], yields [Error::MissingRounds] for
+    /// the round, and skips forward to the next round.
+    ///
+    /// Default: None, watch keeps waiting for rounds that are not yet
+    /// available, and yields other errors without skipping the round,
+    /// backing off as per [Config::backoff] before retrying it.
+    ///
+    /// [Event::MissedRound]: crate::Event::MissedRound
+    pub late_tolerance: Option<time::Duration>,
+    /// Watchdog for a halted drand-group. If no new round is observed for
+    /// `stall_periods` periods, since the scheduled time of the latest
+    /// observed round, client emits [Event::ChainStalled] and fails with
+    /// [Error::ChainStalled]. Requests for the latest round fail for as
+    /// long as the chain is stalled, watch yields the error once for each
+    /// stall and keeps waiting for the next round.
+    ///
+    /// Default: None, no watchdog.
+    ///
+    /// [Event::ChainStalled]: crate::Event::ChainStalled
+    pub stall_periods: Option<u32>,
+    /// Directory to persist chain info, latest verified check_point and
+    /// latency stats of endpoints. Persisted state is loaded by
+    /// [Client::from_config], and updated after boot and as the check_point
+    /// advances, at most once a minute. Config's info, if supplied, takes
+    /// precedence and state persisted for a different chain is ignored.
+    /// Persisted check_point is used if it is later than config's
+    /// check_point. Persisting is best effort, failures are reported with
+    /// [Event::PersistFailure].
+    ///
+    /// Default: None
+    ///
+    /// [Client::from_config]: crate::Client::from_config
+    /// [Event::PersistFailure]: crate::Event::PersistFailure
+    pub state_dir: Option<path::PathBuf>,
+    /// Transport shared with other clients, refer [SharedTransport]. If
+    /// supplied, transport's connection pool and concurrency limit are
+    /// used instead of `max_conns` and `global_concurrency`.
+    ///
+    /// Default: None
+    pub transport: Option<SharedTransport>,
+    /// Endpoints added to the client by [Client::from_config].
+    ///
+    /// Default: empty, endpoints are added with [Client::add_endpoint].
+    ///
+    /// [Client::from_config]: crate::Client::from_config
+    /// [Client::add_endpoint]: crate::Client::add_endpoint
+    pub endpoints: Vec<Endpoint>,
+    /// Root of trust, chain-hash of the drand-group, used when booting the
+    /// client without a chain-hash.
+    ///
+    /// Default: None
+    pub chain_hash: Option<Vec<u8>>,
+    /// Route all requests via proxy at this url. Not applied to endpoints
+    /// added with their own http client, or when `transport` is supplied.
+    ///
+    /// Default: None
+    pub proxy: Option<String>,
+    /// Timeout for each http request, from connect till the response body
+    /// is read. Not applied to endpoints added with their own http client,
+    /// or when `transport` is supplied.
+    ///
+    /// Default: None, requests are not timed out.
+    pub request_timeout: Option<time::Duration>,
+    /// Boot onto a chain whose period is no longer than this, when booting
+    /// without a chain-hash. Chains served by the first endpoint are listed
+    /// from its `/chains` route, and the one with the shortest period is
+    /// picked. Chains this client cannot boot onto, like quicknet with its
+    /// keys on G2, are skipped with [Event::ChainSkipped]. Supplied or
+    /// persisted info, if its period qualifies, is used as is. Boot fails
+    /// with Invalid error if no chain qualifies.
+    ///
+    /// Default: None
+    ///
+    /// [Event::ChainSkipped]: crate::Event::ChainSkipped
+    pub max_period: Option<time::Duration>,
+    /// Maximum number of requests per hour, for metered environments.
+    /// Once exceeded, requests for rounds fail with [Error::BudgetExceeded]
+    /// till the hour is over, and [Event::BudgetExceeded] is emitted once
+    /// for the hour. Hour starts with the first request for a round after
+    /// the previous hour is over. Requests made while booting are counted
+    /// but not failed, refer [Client::to_usage]. Catching up on the chain,
+    /// to verify a requested round, is stopped once the budget is spent.
+    ///
+    /// Default: None, no budget.
+    ///
+    /// [Event::BudgetExceeded]: crate::Event::BudgetExceeded
+    /// [Client::to_usage]: crate::Client::to_usage
+    pub request_budget: Option<u64>,
+    /// Recompute randomness of every round served by relays, as the sha256
+    /// of its signature, ignoring the randomness field served along with
+    /// it. Signatures are verified as per [Config::policy], while served
+    /// randomness is not, recomputing makes the served field purely
+    /// advisory and randomness as trustworthy as the signature, for
+    /// every policy. Refer [recompute_randomness].
+    ///
+    /// Default: false
+    ///
+    /// [recompute_randomness]: crate::recompute_randomness
+    pub recompute_randomness: bool,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             check_point: None,
-            determinism: false,
+            info: None,
+            policy: SecurityPolicy::default(),
             secure: false,
             max_conns: MAX_CONNS,
+            global_concurrency: None,
+            cache_historical: true,
+            hedge: None,
+            catch_up: CatchUp::default(),
+            agreement: Agreement::default(),
+            ip_filter: None,
+            clock: Arc::new(SystemClock),
+            fail_fast: false,
+            backoff: Backoff::default(),
+            breaker: Breaker::default(),
+            early_tolerance: time::Duration::default(),
+            late_tolerance: None,
+            stall_periods: None,
+            state_dir: None,
+            transport: None,
+            endpoints: Vec::default(),
+            chain_hash: None,
+            proxy: None,
+            request_timeout: None,
+            max_period: None,
+            request_budget: None,
+            recompute_randomness: false,
         }
     }
 }
 
+impl FromStr for Config {
+    type Err = Error;
+
+    /// Parse configuration from a TOML document, refer [Config::from_path].
+    fn from_str(s: &str) -> Result<Config> {
+        conffile::from_toml(s)
+    }
+}
+
 impl Config {
+    /// Load configuration from a TOML file. Keys missing in the file take
+    /// their default value, unknown keys fail with Invalid error. Clock,
+    /// check_point, info, ip_filter and transport cannot be configured
+    /// from file, set them on the returned value.
+    pub fn from_path<P: AsRef<path::Path>>(file: P) -> Result<Config> {
+        let data = err_at!(IOError, fs::read_to_string(file.as_ref()))?;
+        data.parse()
+    }
+
     pub fn set_check_point(&mut self, check_point: Option<Random>) -> &mut Self {
         self.check_point = check_point;
         self
     }
 
+    pub fn set_info(&mut self, info: Option<Info>) -> &mut Self {
+        self.info = info;
+        self
+    }
+
+    /// Shorthand for setting the `policy` to SecurityPolicy::Full, if
+    /// `determinism` is true, else to SecurityPolicy::Assumed.
     pub fn set_determinism(&mut self, determinism: bool) -> &mut Self {
-        self.determinism = determinism;
+        self.policy = match determinism {
+            true => SecurityPolicy::Full,
+            false => SecurityPolicy::Assumed,
+        };
+        self
+    }
+
+    pub fn set_security_policy(&mut self, policy: SecurityPolicy) -> &mut Self {
+        self.policy = policy;
         self
     }
 
@@ -65,6 +330,192 @@ impl Config {
         self.max_conns = max_conns;
         self
     }
+
+    pub fn set_global_concurrency(&mut self, n: Option<usize>) -> &mut Self {
+        self.global_concurrency = n;
+        self
+    }
+
+    pub fn set_hedge(&mut self, hedge: Option<Hedge>) -> &mut Self {
+        self.hedge = hedge;
+        self
+    }
+
+    pub fn set_catch_up(&mut self, catch_up: CatchUp) -> &mut Self {
+        self.catch_up = catch_up;
+        self
+    }
+
+    pub fn set_cache_historical(&mut self, cache_historical: bool) -> &mut Self {
+        self.cache_historical = cache_historical;
+        self
+    }
+
+    pub fn set_agreement(&mut self, agreement: Agreement) -> &mut Self {
+        self.agreement = agreement;
+        self
+    }
+
+    pub fn set_ip_filter(&mut self, ip_filter: Option<IpFilter>) -> &mut Self {
+        self.ip_filter = ip_filter;
+        self
+    }
+
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) -> &mut Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn set_fail_fast(&mut self, fail_fast: bool) -> &mut Self {
+        self.fail_fast = fail_fast;
+        self
+    }
+
+    pub fn set_backoff(&mut self, backoff: Backoff) -> &mut Self {
+        self.backoff = backoff;
+        self
+    }
+
+    pub fn set_breaker(&mut self, breaker: Breaker) -> &mut Self {
+        self.breaker = breaker;
+        self
+    }
+
+    pub fn set_early_tolerance(&mut self, early: time::Duration) -> &mut Self {
+        self.early_tolerance = early;
+        self
+    }
+
+    pub fn set_late_tolerance(&mut self, late: Option<time::Duration>) -> &mut Self {
+        self.late_tolerance = late;
+        self
+    }
+
+    pub fn set_stall_periods(&mut self, k: Option<u32>) -> &mut Self {
+        self.stall_periods = k;
+        self
+    }
+
+    pub fn set_state_dir(&mut self, dir: Option<path::PathBuf>) -> &mut Self {
+        self.state_dir = dir;
+        self
+    }
+
+    pub fn set_transport(&mut self, transport: Option<SharedTransport>) -> &mut Self {
+        self.transport = transport;
+        self
+    }
+
+    pub fn set_endpoints(&mut self, endpoints: Vec<Endpoint>) -> &mut Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    pub fn set_chain_hash(&mut self, chain_hash: Option<Vec<u8>>) -> &mut Self {
+        self.chain_hash = chain_hash;
+        self
+    }
+
+    pub fn set_proxy(&mut self, proxy: Option<String>) -> &mut Self {
+        self.proxy = proxy;
+        self
+    }
+
+    pub fn set_request_timeout(&mut self, timeout: Option<time::Duration>) -> &mut Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    pub fn set_max_period(&mut self, period: Option<time::Duration>) -> &mut Self {
+        self.max_period = period;
+        self
+    }
+
+    pub fn set_request_budget(&mut self, budget: Option<u64>) -> &mut Self {
+        self.request_budget = budget;
+        self
+    }
+
+    pub fn set_recompute_randomness(&mut self, recompute: bool) -> &mut Self {
+        self.recompute_randomness = recompute;
+        self
+    }
+}
+
+/// Threshold for hedging requests, refer [Config::hedge].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Hedge {
+    /// Hedge after primary endpoint's latency percentile, a value between
+    /// 1 and 100, computed from its recent requests. Typically 95.
+    Percentile(u8),
+    /// Hedge after a fixed duration.
+    After(time::Duration),
+}
+
+/// Profile for fetching rounds while catching up with the latest round,
+/// refer [Config::catch_up].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum CatchUp {
+    /// Fetch rounds as fast as endpoints allow, concurrently.
+    Eager,
+    /// Fetch rounds one at a time, waiting for `interval` between requests,
+    /// friendly to public relays when verifying from genesis. After every
+    /// PROGRESS_ROUNDS, last verified round is persisted to `progress`
+    /// file, if supplied, so that an interrupted catch-up can resume from
+    /// there on the next boot. Progress is saved along with the chain's
+    /// hash, and is ignored when booting onto another chain.
+    Gentle {
+        interval: time::Duration,
+        progress: Option<path::PathBuf>,
+    },
+}
+
+impl Default for CatchUp {
+    fn default() -> Self {
+        CatchUp::Eager
+    }
+}
+
+/// Policy to pick a response when endpoints disagree on the latest round,
+/// refer [Config::agreement].
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Agreement {
+    /// Pick the response with the higher round.
+    PreferHigher,
+    /// Both endpoints must respond with the same round, else fail with
+    /// Invalid error.
+    RequireMatch,
+    /// Verify both responses with the chain's public key, and pick the
+    /// higher round among the verified responses. Fail with NotSecure
+    /// error if neither response is verified.
+    PreferVerified,
+}
+
+impl Default for Agreement {
+    fn default() -> Self {
+        Agreement::PreferHigher
+    }
+}
+
+/// Policy to verify the chain of randomness while booting a client.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum SecurityPolicy {
+    /// Latest round is assumed as verified. Cheapest, and trusts the
+    /// endpoint for the latest round.
+    Assumed,
+    /// Ensure all rounds generated within the last `duration`, till the
+    /// latest round, are valid. First round of the window is trusted,
+    /// a middle ground between Assumed and Full.
+    Window(time::Duration),
+    /// Ensure all rounds from check_point, or from Round-1, to the latest
+    /// round are valid. Expensive.
+    Full,
+}
+
+impl Default for SecurityPolicy {
+    fn default() -> Self {
+        SecurityPolicy::Assumed
+    }
 }
 
 /// Type alias for Result return type, used by this package.
@@ -74,6 +525,7 @@ pub type Result<T> = result::Result<T, Error>;
 ///
 /// Each variant carries a prefix, typically identifying the
 /// error location.
+#[derive(Clone)]
 pub enum Error {
     Fatal(String, String),
     PoisonedLock(String, String),
@@ -83,6 +535,33 @@ pub enum Error {
     JsonParse(String, String),
     StringParse(String, String),
     HexParse(String, String),
+    Timeout(String, String),
+    Equivocation(String, String),
+    /// Rounds needed to verify the chain are not available with any of the
+    /// endpoints, refer [Client::accept_gap] to continue past them.
+    ///
+    /// [Client::accept_gap]: crate::Client::accept_gap
+    MissingRounds(String, ops::RangeInclusive<u128>),
+    /// Round is not yet generated by the drand-group, relay responded with
+    /// 425 Too Early, along with the Retry-After duration if supplied.
+    RoundNotYetAvailable(String, u128, Option<time::Duration>),
+    /// Round of randomness failed verification, refer [VerifyError].
+    Verify(String, VerifyError),
+    /// Client is poisoned by an earlier integrity failure, in fail-fast
+    /// mode, refer [Config::fail_fast].
+    PoisonedByIncident(String, Box<Incident>),
+    /// No new round is observed since the latest observed round, for
+    /// the duration, refer [Config::stall_periods].
+    ChainStalled(String, u128, time::Duration),
+    /// Relay responded with 429 Too Many Requests, along with the
+    /// Retry-After duration if supplied. Endpoint is paused, refer
+    /// [Event::RateLimited].
+    ///
+    /// [Event::RateLimited]: crate::Event::RateLimited
+    RateLimited(String, Option<time::Duration>),
+    /// Client exceeded its budget, the number of requests per hour, refer
+    /// [Config::request_budget].
+    BudgetExceeded(String, u64),
 }
 
 impl fmt::Display for Error {
@@ -98,10 +577,148 @@ impl fmt::Display for Error {
             JsonParse(p, msg) => write!(f, "{} JsonParse: {}", p, msg),
             StringParse(p, msg) => write!(f, "{} StringParse: {}", p, msg),
             HexParse(p, msg) => write!(f, "{} HexParse: {}", p, msg),
+            Timeout(p, msg) => write!(f, "{} Timeout: {}", p, msg),
+            Equivocation(p, msg) => write!(f, "{} Equivocation: {}", p, msg),
+            MissingRounds(p, r) => write!(f, "{} MissingRounds: {}..={}", p, r.start(), r.end()),
+            RoundNotYetAvailable(p, round, after) => {
+                let after = after.map(|a| format!(", retry after {:?}", a));
+                let after = after.unwrap_or_default();
+                write!(f, "{} RoundNotYetAvailable: round {}{}", p, round, after)
+            }
+            Verify(p, err) => write!(f, "{} Verify: {}", p, err),
+            PoisonedByIncident(p, incident) => {
+                write!(f, "{} PoisonedByIncident: {}", p, incident.error)
+            }
+            ChainStalled(p, round, elapsed) => {
+                write!(f, "{} ChainStalled: no round after {} for {:?}", p, round, elapsed)
+            }
+            RateLimited(p, after) => {
+                let after = after.map(|a| format!(", retry after {:?}", a));
+                write!(f, "{} RateLimited{}", p, after.unwrap_or_default())
+            }
+            BudgetExceeded(p, budget) => {
+                write!(f, "{} BudgetExceeded: {} requests per hour", p, budget)
+            }
         }
     }
 }
 
+/// Kind of error, to construct [Error] values outside this crate, like
+/// from alternative transports, refer [Error::new].
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum ErrorKind {
+    Fatal,
+    PoisonedLock,
+    NotSecure,
+    Invalid,
+    IOError,
+    JsonParse,
+    StringParse,
+    HexParse,
+    Timeout,
+    Equivocation,
+}
+
+impl Error {
+    /// Create a new error of `kind`, with message `msg`. Error's prefix
+    /// is the caller's location. Errors that carry data, like
+    /// MissingRounds and RoundNotYetAvailable, can be constructed directly.
+    #[track_caller]
+    pub fn new<M: ToString>(kind: ErrorKind, msg: M) -> Error {
+        let loc = std::panic::Location::caller();
+        let (p, msg) = (format!("{}:{}", loc.file(), loc.line()), msg.to_string());
+        match kind {
+            ErrorKind::Fatal => Error::Fatal(p, msg),
+            ErrorKind::PoisonedLock => Error::PoisonedLock(p, msg),
+            ErrorKind::NotSecure => Error::NotSecure(p, msg),
+            ErrorKind::Invalid => Error::Invalid(p, msg),
+            ErrorKind::IOError => Error::IOError(p, msg),
+            ErrorKind::JsonParse => Error::JsonParse(p, msg),
+            ErrorKind::StringParse => Error::StringParse(p, msg),
+            ErrorKind::HexParse => Error::HexParse(p, msg),
+            ErrorKind::Timeout => Error::Timeout(p, msg),
+            ErrorKind::Equivocation => Error::Equivocation(p, msg),
+        }
+    }
+
+    /// Append the underlying `source` of this error to its message. Errors
+    /// without a message, like MissingRounds, are returned as is.
+    pub fn with_source<E: fmt::Display>(self, source: E) -> Error {
+        self.map(|p| p, |msg| format!("{}: {}", msg, source))
+    }
+
+    // used by err_at! macro to re-prefix errors with the call site.
+    #[doc(hidden)]
+    pub fn with_prefix(self, prefix: String) -> Error {
+        self.map(|_| prefix, |msg| msg)
+    }
+
+    fn map<P, M>(self, fp: P, fm: M) -> Error
+    where
+        P: FnOnce(String) -> String,
+        M: FnOnce(String) -> String,
+    {
+        use Error::*;
+
+        match self {
+            Fatal(p, msg) => Fatal(fp(p), fm(msg)),
+            PoisonedLock(p, msg) => PoisonedLock(fp(p), fm(msg)),
+            NotSecure(p, msg) => NotSecure(fp(p), fm(msg)),
+            Invalid(p, msg) => Invalid(fp(p), fm(msg)),
+            IOError(p, msg) => IOError(fp(p), fm(msg)),
+            JsonParse(p, msg) => JsonParse(fp(p), fm(msg)),
+            StringParse(p, msg) => StringParse(fp(p), fm(msg)),
+            HexParse(p, msg) => HexParse(fp(p), fm(msg)),
+            Timeout(p, msg) => Timeout(fp(p), fm(msg)),
+            Equivocation(p, msg) => Equivocation(fp(p), fm(msg)),
+            MissingRounds(p, rounds) => MissingRounds(fp(p), rounds),
+            RoundNotYetAvailable(p, round, after) => RoundNotYetAvailable(fp(p), round, after),
+            Verify(p, err) => Verify(fp(p), err),
+            PoisonedByIncident(p, incident) => PoisonedByIncident(fp(p), incident),
+            ChainStalled(p, round, elapsed) => ChainStalled(fp(p), round, elapsed),
+            RateLimited(p, after) => RateLimited(fp(p), after),
+            BudgetExceeded(p, budget) => BudgetExceeded(fp(p), budget),
+        }
+    }
+
+    /// Return the kind of this error, None for errors that carry data.
+    pub fn to_kind(&self) -> Option<ErrorKind> {
+        use Error::*;
+
+        match self {
+            Fatal(_, _) => Some(ErrorKind::Fatal),
+            PoisonedLock(_, _) => Some(ErrorKind::PoisonedLock),
+            NotSecure(_, _) => Some(ErrorKind::NotSecure),
+            Invalid(_, _) => Some(ErrorKind::Invalid),
+            IOError(_, _) => Some(ErrorKind::IOError),
+            JsonParse(_, _) => Some(ErrorKind::JsonParse),
+            StringParse(_, _) => Some(ErrorKind::StringParse),
+            HexParse(_, _) => Some(ErrorKind::HexParse),
+            Timeout(_, _) => Some(ErrorKind::Timeout),
+            Equivocation(_, _) => Some(ErrorKind::Equivocation),
+            MissingRounds(_, _) => None,
+            RoundNotYetAvailable(_, _, _) => None,
+            Verify(_, _) => None,
+            PoisonedByIncident(_, _) => None,
+            ChainStalled(_, _, _) => None,
+            RateLimited(_, _) => None,
+            BudgetExceeded(_, _) => None,
+        }
+    }
+}
+
+/// Integrity failure that poisoned a client, in fail-fast mode, refer
+/// [Config::fail_fast].
+#[derive(Clone, Debug)]
+pub struct Incident {
+    /// Time of the incident.
+    pub at: time::SystemTime,
+    /// Requested round, if any.
+    pub round: Option<u128>,
+    /// Verification failure, equivocation or chain-info mismatch.
+    pub error: Error,
+}
+
 impl fmt::Debug for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
         write!(f, "{}", self)
@@ -117,15 +734,15 @@ impl error::Error for Error {}
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct Info {
     /// Distributed public key of the drand group.
-    pub public_key: Vec<u8>,
-    /// Time in seconds between randomness beacon rounds.
+    pub public_key: PublicKey,
+    /// Time between randomness beacon rounds, can be less than a second.
     pub period: time::Duration,
     /// Time in seconds since the Unix Epoch that the group began generating
     /// randomness
     pub genesis_time: time::SystemTime,
     /// Chain-hash, which uniquely identifies the drand chain. It is used as
     /// a root of trust for validation of the first round of randomness.
-    pub hash: Vec<u8>,
+    pub hash: ChainHash,
     /// Use as previous_signature to validate the first round of randomness.
     pub group_hash: Vec<u8>,
 }
@@ -133,15 +750,98 @@ pub struct Info {
 impl Default for Info {
     fn default() -> Self {
         Info {
-            public_key: Vec::default(),
+            public_key: PublicKey::default(),
             period: time::Duration::default(),
             genesis_time: time::UNIX_EPOCH,
-            hash: Vec::default(),
+            hash: ChainHash::default(),
             group_hash: Vec::default(),
         }
     }
 }
 
+/// Strictness level for comparing chain info, refer [Info::matches].
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Strictness {
+    /// Compare only the distributed public key.
+    Keys,
+    /// Compare the distributed public key and the chain-hash.
+    KeysHash,
+    /// Compare all fields, including period, genesis_time and group_hash.
+    Full,
+}
+
+impl Info {
+    /// Return whether `other` chain info matches with this chain info,
+    /// for the given `strictness`.
+    pub fn matches(&self, other: &Info, strictness: Strictness) -> bool {
+        self.validate(other, strictness).is_ok()
+    }
+
+    /// Same as [Info::matches], but on mismatch return a NotSecure error
+    /// describing the first mismatching field.
+    pub fn validate(&self, other: &Info, strictness: Strictness) -> Result<()> {
+        let (keys, hash, full) = match strictness {
+            Strictness::Keys => (true, false, false),
+            Strictness::KeysHash => (true, true, false),
+            Strictness::Full => (true, true, true),
+        };
+
+        if keys && self.public_key != other.public_key {
+            let (x, y) = (&self.public_key, &other.public_key);
+            err_at!(NotSecure, msg: format!("public-key {} != {}", x, y))
+        } else if hash && self.hash != other.hash {
+            let (x, y) = (&self.hash, &other.hash);
+            err_at!(NotSecure, msg: format!("hash {} != {}", x, y))
+        } else if full && self.period != other.period {
+            let (x, y) = (self.period, other.period);
+            err_at!(NotSecure, msg: format!("period {:?} != {:?}", x, y))
+        } else if full && self.genesis_time != other.genesis_time {
+            let (x, y) = (self.genesis_time, other.genesis_time);
+            err_at!(NotSecure, msg: format!("genesis_time {:?} != {:?}", x, y))
+        } else if full && self.group_hash != other.group_hash {
+            let x = hex::encode(&self.group_hash);
+            let y = hex::encode(&other.group_hash);
+            err_at!(NotSecure, msg: format!("group_hash {} != {}", x, y))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Return the most recent round of randomness that is available at
+    /// time `t`. Returns 0 if `t` is earlier than the genesis_time.
+    pub fn round_at(&self, t: time::SystemTime) -> Result<u128> {
+        let period = match self.period.as_nanos() {
+            0 => err_at!(Invalid, msg: format!("zero period"))?,
+            period => period,
+        };
+        match t.duration_since(self.genesis_time) {
+            // round 1 starts at genesis time.
+            Ok(dur) => Ok((dur.as_nanos() / period) + 1),
+            Err(_) => Ok(0),
+        }
+    }
+
+    /// Return the time at which `round` shall be generated by the
+    /// drand-group.
+    pub fn round_time(&self, round: u128) -> Result<time::SystemTime> {
+        let nanos = match round {
+            0 => 0,
+            round => match self.period.as_nanos().checked_mul(round - 1) {
+                Some(nanos) => nanos,
+                None => err_at!(Invalid, msg: format!("round {} overflow", round))?,
+            },
+        };
+        let dur = {
+            let secs = err_at!(Invalid, u64::try_from(nanos / 1_000_000_000))?;
+            time::Duration::new(secs, (nanos % 1_000_000_000) as u32)
+        };
+        match self.genesis_time.checked_add(dur) {
+            Some(t) => Ok(t),
+            None => err_at!(Invalid, msg: format!("round {} overflow", round)),
+        }
+    }
+}
+
 /// Type captures randomness from drand-group for a single round.
 ///
 /// This randomness can be verified at the client side using root-of-trust
@@ -151,24 +851,287 @@ pub struct Random {
     /// Sequentially increasing integer - the randomness round index.
     pub round: u128,
     /// SHA-256 hash of the signature.
-    pub randomness: Vec<u8>,
+    pub randomness: Randomness,
     /// Boneh-Lynn-Shacham (BLS) signature for this round of randomness.
-    pub signature: Vec<u8>,
+    pub signature: Signature,
     /// Signature of the previous round of randomness.
     pub previous_signature: Vec<u8>,
 }
 
+/// Aggregate over consecutive rounds of randomness, refer
+/// [Client::watch_aggregated].
+///
+/// [Client::watch_aggregated]: crate::Client::watch_aggregated
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Aggregate {
+    /// Rounds in the aggregate, in ascending order.
+    pub rounds: Vec<u128>,
+    /// SHA-256 over the randomness of each round, concatenated in the
+    /// order of `rounds`.
+    pub digest: [u8; 32],
+    /// Aggregated rounds of randomness.
+    pub randoms: Vec<Random>,
+}
+
+impl Aggregate {
+    /// Aggregate `randoms`, sorted by their round.
+    pub fn from_randoms(mut randoms: Vec<Random>) -> Aggregate {
+        randoms.sort_by_key(|r| r.round);
+
+        let mut hasher = Sha256::default();
+        for r in randoms.iter() {
+            hasher.update(r.randomness.as_bytes());
+        }
+        let mut digest = [0_u8; 32];
+        digest.copy_from_slice(&hasher.finalize());
+
+        Aggregate {
+            rounds: randoms.iter().map(|r| r.round).collect(),
+            digest,
+            randoms,
+        }
+    }
+}
+
+/// Estimated cost of booting a client with its configured security policy,
+/// refer [Client::plan_boot]. Operators can use it to decide whether to
+/// proceed, switch policy, or supply a check_point instead.
+///
+/// [Client::plan_boot]: crate::Client::plan_boot
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct BootPlan {
+    /// Security policy configured for the client.
+    pub policy: SecurityPolicy,
+    /// Round from which chain shall be verified, None for
+    /// SecurityPolicy::Assumed.
+    pub from: Option<u128>,
+    /// Latest round, as reported by the endpoint.
+    pub latest: u128,
+    /// Number of rounds to fetch and verify.
+    pub rounds: u128,
+    /// Observed cost of fetching and verifying a single round, fetch cost
+    /// is derived from endpoint's latency and the catch-up profile.
+    pub per_round: time::Duration,
+    /// Estimated duration of the catch-up, `rounds * per_round`.
+    pub eta: time::Duration,
+}
+
+/// Requests sent, bytes downloaded and rounds verified by a client,
+/// across all its endpoints, refer [Client::to_usage].
+///
+/// [Client::to_usage]: crate::Client::to_usage
+#[derive(Clone, Copy, Default, Eq, PartialEq, Debug)]
+pub struct Usage {
+    /// Number of requests sent, including those that failed.
+    pub requests: u64,
+    /// Bytes of response bodies downloaded.
+    pub bytes: u64,
+    /// Number of rounds verified, including failed verifications.
+    pub verified: u64,
+}
+
+/// Report from booting a client with a deadline, refer
+/// [Client::boot_with_deadline].
+///
+/// [Client::boot_with_deadline]: crate::Client::boot_with_deadline
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct BootReport {
+    /// Security policy configured for the client.
+    pub requested: SecurityPolicy,
+    /// Security policy the client is booted with, differs from `requested`
+    /// if boot did not complete within the deadline.
+    pub policy: SecurityPolicy,
+    /// Time taken to boot the client.
+    pub elapsed: time::Duration,
+    /// Verified check_point after boot, if any.
+    pub check_point: Option<u128>,
+}
+
+impl BootReport {
+    /// Return whether the security policy was downgraded to meet the
+    /// deadline.
+    pub fn is_degraded(&self) -> bool {
+        self.policy != self.requested
+    }
+}
+
+/// Report from checking an endpoint, refer [Client::validate_endpoints].
+///
+/// [Client::validate_endpoints]: crate::Client::validate_endpoints
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct EndpointCheck {
+    /// Base url of the endpoint.
+    pub url: String,
+    /// Category of the endpoint.
+    pub category: Category,
+    /// Chain info served by the endpoint, None if it is not reachable.
+    pub info: Option<Info>,
+    /// Latest round served by the endpoint.
+    pub latest: Option<u128>,
+    /// Number of rounds the latest round is behind the current round, as
+    /// per client's clock.
+    pub lag: Option<u128>,
+    /// Time taken to fetch chain info and the latest round.
+    pub latency: time::Duration,
+    /// First problem found with the endpoint, None if it is healthy.
+    pub error: Option<String>,
+}
+
+impl EndpointCheck {
+    /// Return whether the endpoint is reachable, agrees on chain info and
+    /// serves a fresh latest round.
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Report from migrating a check_point from a legacy chain to a new chain,
+/// refer [Client::migrate_check_point].
+///
+/// [Client::migrate_check_point]: crate::Client::migrate_check_point
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Migration {
+    /// Chain-hash of the legacy chain.
+    pub legacy_hash: ChainHash,
+    /// Legacy check_point, validated against the archived legacy chain.
+    pub legacy_check_point: Random,
+    /// Latest archived round of the legacy chain, verified as chained to
+    /// the legacy check_point.
+    pub legacy_latest: Random,
+    /// Verified check_point established on the new chain.
+    pub check_point: Option<Random>,
+}
+
+/// Verdict on a beacon received from a third party, refer
+/// [Client::verify_external].
+///
+/// [Client::verify_external]: crate::Client::verify_external
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Verdict {
+    /// Beacon is signed by the chain, and is consistent with the verified
+    /// check_point, if any.
+    Valid { round: u128 },
+    /// Beacon is malformed, round is zero or randomness is not the hash of
+    /// the signature.
+    Malformed { round: u128, msg: String },
+    /// Beacon is not signed by the chain this client trusts.
+    BadSignature(VerifyError),
+    /// Beacon is signed by the chain, but conflicts with the verified
+    /// check_point, same round with different bytes, or not chained to it.
+    Conflict { round: u128, check_point: u128 },
+}
+
+impl Verdict {
+    /// Return whether beacon can be trusted.
+    pub fn is_valid(&self) -> bool {
+        matches!(self, Verdict::Valid { .. })
+    }
+}
+
+/// Report from re-verifying a range of historical rounds, refer
+/// [Client::reverify].
+///
+/// [Client::reverify]: crate::Client::reverify
+#[derive(Clone, Default, Eq, PartialEq, Debug)]
+pub struct ReverifyReport {
+    /// First round of the range.
+    pub from: u128,
+    /// Last round of the range, inclusive.
+    pub till: u128,
+    /// Number of rounds that passed verification.
+    pub verified: u128,
+    /// Rounds that failed verification, along with the reason.
+    pub failures: Vec<(u128, String)>,
+}
+
+impl ReverifyReport {
+    /// Return whether every round in the range passed verification.
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty() && self.verified == (self.till.saturating_sub(self.from) + 1)
+    }
+}
+
 impl fmt::Display for Random {
     fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
         write!(f, "Random<{}>", self.round)
     }
 }
 
+/// Randomness of the round, as bytes.
+impl AsRef<[u8]> for Random {
+    fn as_ref(&self) -> &[u8] {
+        self.randomness.as_bytes()
+    }
+}
+
+/// Round and its randomness, as `(round, seed)` pair. There is no
+/// conversion the other way, a beacon without signature cannot be
+/// verified.
+impl From<Random> for (u128, [u8; Randomness::SIZE]) {
+    fn from(r: Random) -> Self {
+        (r.round, r.randomness.0)
+    }
+}
+
+impl From<&Random> for (u128, [u8; Randomness::SIZE]) {
+    fn from(r: &Random) -> Self {
+        (r.round, r.randomness.0)
+    }
+}
+
+/// Parse randomness from json string, in the format served by drand's
+/// http api. Refer [Random::validate] for validations.
+impl FromStr for Random {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Random> {
+        let r: RandomJson = err_at!(JsonParse, serde_json::from_str(s))?;
+        let r: Random = r.try_into()?;
+        r.validate()?;
+        Ok(r)
+    }
+}
+
+/// Convert parsed json value, in the format served by drand's http api,
+/// into randomness. Refer [Random::validate] for validations.
+impl TryFrom<&serde_json::Value> for Random {
+    type Error = Error;
+
+    fn try_from(val: &serde_json::Value) -> Result<Random> {
+        let r = err_at!(JsonParse, RandomJson::deserialize(val))?;
+        let r: Random = r.try_into()?;
+        r.validate()?;
+        Ok(r)
+    }
+}
+
 impl Random {
+    /// Validate fields of randomness, without verifying its signature.
+    /// Round must be non-zero and randomness must be the SHA-256 hash of
+    /// the signature. Use [OfflineClient] or [Client] to verify the
+    /// signature with the chain's public key.
+    ///
+    /// [OfflineClient]: crate::OfflineClient
+    /// [Client]: crate::Client
+    pub fn validate(&self) -> Result<()> {
+        if self.round == 0 {
+            err_at!(Invalid, msg: format!("round 0"))?
+        }
+        let digest = Sha256::digest(self.signature.as_bytes());
+        if digest.as_slice() != self.randomness.as_bytes() {
+            err_at!(Invalid, msg: format!("{} randomness is not hash of signature", self))?
+        }
+        Ok(())
+    }
+
+    /// Return the message signed for this round, under the chained scheme
+    /// used by mainnet. Refer [Scheme::message_for_round] for other
+    /// schemes.
     pub fn to_digest(&self) -> Result<Vec<u8>> {
-        let mut hasher = Sha256::default();
-        hasher.update(&self.previous_signature);
-        hasher.update(self.round.to_be_bytes());
-        Ok(hasher.finalize().to_vec())
+        Scheme::PedersenBlsChained.message_for_round(self.round, &self.previous_signature)
     }
 }
+
+#[cfg(test)]
+#[path = "core_test.rs"]
+mod core_test;
@@ -0,0 +1,150 @@
+use super::*;
+
+#[test]
+fn test_info_matches() {
+    let info = Info {
+        public_key: PublicKey([1; 48]),
+        period: time::Duration::from_secs(30),
+        genesis_time: time::UNIX_EPOCH + time::Duration::from_secs(1595431050),
+        hash: ChainHash([2; 32]),
+        group_hash: vec![3; 32],
+    };
+
+    assert!(info.matches(&info, Strictness::Full));
+
+    let other = Info {
+        period: time::Duration::from_secs(3),
+        group_hash: vec![4; 32],
+        ..info.clone()
+    };
+    assert!(info.matches(&other, Strictness::Keys));
+    assert!(info.matches(&other, Strictness::KeysHash));
+    assert!(!info.matches(&other, Strictness::Full));
+
+    let other = Info {
+        hash: ChainHash([5; 32]),
+        ..info.clone()
+    };
+    assert!(info.matches(&other, Strictness::Keys));
+    assert!(!info.matches(&other, Strictness::KeysHash));
+    assert!(info.validate(&other, Strictness::KeysHash).is_err());
+
+    let other = Info {
+        public_key: PublicKey([6; 48]),
+        ..info.clone()
+    };
+    assert!(!info.matches(&other, Strictness::Keys));
+}
+
+#[test]
+fn test_error_new() {
+    let err = Error::new(ErrorKind::IOError, "connection reset");
+    match &err {
+        Error::IOError(p, msg) => {
+            assert!(p.starts_with(file!()), "{}", p);
+            assert_eq!(msg, "connection reset");
+        }
+        err => panic!("unexpected {}", err),
+    }
+    assert_eq!(err.to_kind(), Some(ErrorKind::IOError));
+
+    let err = Error::new(ErrorKind::Timeout, "fetch").with_source("deadline exceeded");
+    match &err {
+        Error::Timeout(_, msg) => assert_eq!(msg, "fetch: deadline exceeded"),
+        err => panic!("unexpected {}", err),
+    }
+
+    let err = Error::MissingRounds("here".to_string(), 1..=2).with_source("source");
+    assert_eq!(err.to_kind(), None);
+    match err {
+        Error::MissingRounds(p, rounds) => {
+            assert_eq!(p, "here");
+            assert_eq!(rounds, 1..=2);
+        }
+        err => panic!("unexpected {}", err),
+    }
+
+    let res: Result<()> = Err(Error::new(ErrorKind::Invalid, "bad"));
+    match err_at!(res) {
+        Err(Error::Invalid(p, msg)) => {
+            assert_eq!(p, format!("{}:{}", file!(), line!() - 2));
+            assert_eq!(msg, "bad");
+        }
+        res => panic!("unexpected {:?}", res),
+    }
+}
+
+#[test]
+fn test_random_parse() {
+    let data = r#"{
+        "round": 1,
+        "randomness": "101297f1ca7dc44ef6088d94ad5fb7ba03455dc33d53ddb412bbc4564ed986ec",
+        "signature": "8d61d9100567de44682506aea1a7a6fa6e5491cd27a0a0ed349ef6910ac5ac20ff7bc3e09d7c046566c9f7f3c6f3b10104990e7cb424998203d8f7de586fb7fa5f60045417a432684f85093b06ca91c769f0e7ca19268375e659c2a2352b4655",
+        "previous_signature": "176f93498eac9ca337150b46d21dd58673ea4e3581185f869672e59fa4cb390a"
+    }"#;
+    let r: Random = data.parse().unwrap();
+    assert_eq!(r.round, 1);
+    assert_eq!(
+        hex::encode(&r.previous_signature),
+        "176f93498eac9ca337150b46d21dd58673ea4e3581185f869672e59fa4cb390a"
+    );
+
+    let val: serde_json::Value = serde_json::from_str(data).unwrap();
+    assert_eq!(Random::try_from(&val).unwrap(), r);
+    assert_eq!(
+        hex::encode(r.to_digest().unwrap()),
+        "3bdd496a35f3c3b6b4318da8b77d3ce4793cbdf26e08156f6fd8c49e85b727cb"
+    );
+
+    // randomness is not the hash of signature.
+    let bad = data.replace("101297f1", "101297f2");
+    assert!(bad.parse::<Random>().is_err());
+    let val: serde_json::Value = serde_json::from_str(&bad).unwrap();
+    assert!(Random::try_from(&val).is_err());
+
+    // round zero, short signature, missing field, not json.
+    assert!(data.replace("\"round\": 1", "\"round\": 0").parse::<Random>().is_err());
+    assert!(data.replace("8d61d910", "").parse::<Random>().is_err());
+    assert!(data.replace("\"round\": 1,", "").parse::<Random>().is_err());
+    assert!("beacon".parse::<Random>().is_err());
+}
+
+#[test]
+fn test_random_conversions() {
+    let r = Random {
+        round: 7,
+        randomness: Randomness([3; 32]),
+        signature: Signature::default(),
+        previous_signature: vec![],
+    };
+
+    assert_eq!(r.as_ref(), &[3_u8; 32][..]);
+    let (round, seed): (u128, [u8; 32]) = (&r).into();
+    assert_eq!((round, seed), (7, [3; 32]));
+    let pair: (u128, [u8; 32]) = r.clone().into();
+    assert_eq!(pair, (7, [3; 32]));
+    let seed: [u8; 32] = r.randomness.into();
+    assert_eq!(seed, [3; 32]);
+}
+
+#[test]
+fn test_aggregate() {
+    let new_random = |round: u128, byte: u8| Random {
+        round,
+        randomness: Randomness([byte; 32]),
+        signature: Signature::default(),
+        previous_signature: vec![],
+    };
+
+    let agg = Aggregate::from_randoms(vec![new_random(6, 2), new_random(5, 1)]);
+    assert_eq!(agg.rounds, vec![5, 6]);
+    assert_eq!(agg.randoms[0].round, 5);
+
+    let mut hasher = Sha256::default();
+    hasher.update(&[1; 32]);
+    hasher.update(&[2; 32]);
+    assert_eq!(&agg.digest[..], &hasher.finalize()[..]);
+
+    let other = Aggregate::from_randoms(vec![new_random(5, 2), new_random(6, 1)]);
+    assert_ne!(other.digest, agg.digest);
+}
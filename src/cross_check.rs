@@ -0,0 +1,169 @@
+//! Module implement a composite client, that cross-checks every round
+//! from a primary client against an independent secondary client, refer
+//! [CrossCheck]. No push based transport, like gossipsub, is included
+//! with this crate, primary and secondary can be any [DrandClient].
+
+use std::{sync::Arc, time};
+
+use crate::{
+    backoff::Retry, Backoff, Clock, DrandClient, Error, Info, Random, Result, Strictness,
+    SystemClock,
+};
+
+/// Composite client, serving rounds from `primary` only after they are
+/// cross-checked against `secondary`. Typically primary is a low latency
+/// source, like a push based transport, and secondary is a [Client]
+/// polling a different relay, so that a round is emitted only when two
+/// independent sources agree on it.
+///
+/// Rounds that differ between the two fail with [Error::Equivocation].
+/// A low latency primary can serve a round before secondary has it, so
+/// secondary is retried, with backoff, while it fails with
+/// [Error::RoundNotYetAvailable], till `tolerance` after the round's
+/// scheduled time, refer [CrossCheck::set_tolerance]. If secondary fails
+/// to serve the round, its error is returned instead of the round. Gaps
+/// yielded by primary's watch, as [Error::MissingRounds], are passed
+/// through as is.
+///
+/// [Client]: crate::Client
+/// [Error::Equivocation]: crate::Error::Equivocation
+/// [Error::RoundNotYetAvailable]: crate::Error::RoundNotYetAvailable
+/// [Error::MissingRounds]: crate::Error::MissingRounds
+pub struct CrossCheck<P, S> {
+    primary: P,
+    secondary: S,
+    info: Info,
+    tolerance: time::Duration,
+    backoff: Backoff,
+    clock: Arc<dyn Clock>,
+}
+
+impl<P, S> CrossCheck<P, S>
+where
+    P: DrandClient,
+    S: DrandClient,
+{
+    /// Create a composite client. Chain info of both clients must match,
+    /// else NotSecure error is returned.
+    pub fn new(primary: P, secondary: S) -> Result<CrossCheck<P, S>> {
+        let (info, y) = (primary.to_info()?, secondary.to_info()?);
+        info.validate(&y, Strictness::KeysHash)?;
+        let val = CrossCheck {
+            primary,
+            secondary,
+            info,
+            tolerance: time::Duration::from_secs(5),
+            backoff: Backoff::default(),
+            clock: Arc::new(SystemClock),
+        };
+        Ok(val)
+    }
+
+    /// Keep retrying secondary for a round till `tolerance` after the
+    /// round's scheduled time. Default: 5 seconds.
+    pub fn set_tolerance(&mut self, tolerance: time::Duration) -> &mut Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Backoff between retries to secondary. Default: [Backoff::default].
+    pub fn set_backoff(&mut self, backoff: Backoff) -> &mut Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Clock used to wait between retries. Default: [SystemClock].
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) -> &mut Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Return the primary and secondary clients.
+    pub fn into_inner(self) -> (P, S) {
+        (self.primary, self.secondary)
+    }
+}
+
+impl<P, S> DrandClient for CrossCheck<P, S>
+where
+    P: DrandClient,
+    S: DrandClient,
+{
+    fn to_info(&self) -> Result<Info> {
+        self.primary.to_info()
+    }
+
+    fn round_at(&self, t: time::SystemTime) -> Result<u128> {
+        self.primary.round_at(t)
+    }
+
+    fn get(&mut self, round: Option<u128>) -> Result<Random> {
+        let (primary, mut checker) = self.split();
+        let r = primary.get(round)?;
+        checker.check(r)
+    }
+
+    fn watch(&mut self) -> Result<Box<dyn Iterator<Item = Result<Random>> + '_>> {
+        let (primary, mut checker) = self.split();
+        let iter = primary.watch()?;
+        Ok(Box::new(iter.map(move |item| checker.check(item?))))
+    }
+}
+
+impl<P, S> CrossCheck<P, S> {
+    // split the borrow, primary apart from the checker on secondary.
+    fn split(&mut self) -> (&mut P, Checker<'_, S>) {
+        let checker = Checker {
+            secondary: &mut self.secondary,
+            info: &self.info,
+            tolerance: self.tolerance,
+            backoff: &self.backoff,
+            clock: self.clock.as_ref(),
+        };
+        (&mut self.primary, checker)
+    }
+}
+
+// borrows secondary and retry settings, apart from primary.
+struct Checker<'a, S> {
+    secondary: &'a mut S,
+    info: &'a Info,
+    tolerance: time::Duration,
+    backoff: &'a Backoff,
+    clock: &'a dyn Clock,
+}
+
+impl<'a, S: DrandClient> Checker<'a, S> {
+    // fetch `r.round` from secondary and return `r` if both agree.
+    fn check(&mut self, r: Random) -> Result<Random> {
+        let other = self.get(r.round)?;
+        let same = other.round == r.round && other.signature == r.signature;
+        if !same || other.randomness != r.randomness {
+            err_at!(Equivocation, msg: format!("round {} differs with secondary", r.round))?
+        }
+        Ok(r)
+    }
+
+    // retry while secondary is yet to see the round, within tolerance.
+    fn get(&mut self, round: u128) -> Result<Random> {
+        let mut retry = Retry::new(self.backoff, self.clock);
+        loop {
+            let err = match self.secondary.get(Some(round)) {
+                Ok(r) => break Ok(r),
+                Err(err @ Error::RoundNotYetAvailable(_, _, _)) => err,
+                Err(err) => break Err(err),
+            };
+            let deadline = self.info.round_time(round)? + self.tolerance;
+            match deadline.duration_since(self.clock.now()) {
+                Ok(remaining) if remaining > time::Duration::default() => {
+                    retry.sleep(retry.to_delay(&err, remaining))
+                }
+                _ => break Err(err),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "cross_check_test.rs"]
+mod cross_check_test;
@@ -0,0 +1,158 @@
+use std::sync::Mutex;
+
+use crate::{fixtures::new_random, ChainHash, Error};
+
+use super::*;
+
+// serves rounds as is, without verification.
+struct Rounds {
+    info: Info,
+    rounds: Vec<Random>,
+}
+
+impl DrandClient for Rounds {
+    fn to_info(&self) -> Result<Info> {
+        Ok(self.info.clone())
+    }
+
+    fn round_at(&self, t: time::SystemTime) -> Result<u128> {
+        self.info.round_at(t)
+    }
+
+    fn get(&mut self, round: Option<u128>) -> Result<Random> {
+        let r = match round {
+            Some(round) => self.rounds.iter().find(|r| r.round == round),
+            None => self.rounds.last(),
+        };
+        match r {
+            Some(r) => Ok(r.clone()),
+            None => err_at!(Invalid, msg: format!("round {:?} missing", round)),
+        }
+    }
+
+    fn watch(&mut self) -> Result<Box<dyn Iterator<Item = Result<Random>> + '_>> {
+        Ok(Box::new(self.rounds.iter().cloned().map(Ok)))
+    }
+}
+
+// secondary that is yet to see any round, for the first `pending` gets.
+struct Late {
+    rounds: Rounds,
+    pending: usize,
+}
+
+impl DrandClient for Late {
+    fn to_info(&self) -> Result<Info> {
+        self.rounds.to_info()
+    }
+
+    fn round_at(&self, t: time::SystemTime) -> Result<u128> {
+        self.rounds.round_at(t)
+    }
+
+    fn get(&mut self, round: Option<u128>) -> Result<Random> {
+        match self.pending {
+            0 => self.rounds.get(round),
+            _ => {
+                self.pending -= 1;
+                let prefix = format!("{}:{}", file!(), line!());
+                Err(Error::RoundNotYetAvailable(prefix, round.unwrap_or(0), None))
+            }
+        }
+    }
+
+    fn watch(&mut self) -> Result<Box<dyn Iterator<Item = Result<Random>> + '_>> {
+        self.rounds.watch()
+    }
+}
+
+#[derive(Debug)]
+struct SimClock(Mutex<time::SystemTime>);
+
+impl Clock for SimClock {
+    fn now(&self) -> time::SystemTime {
+        *self.0.lock().unwrap()
+    }
+
+    fn sleep(&self, dur: time::Duration) {
+        *self.0.lock().unwrap() += dur
+    }
+}
+
+#[test]
+fn test_cross_check() {
+    let info = Info {
+        hash: ChainHash([1; 32]),
+        ..Info::default()
+    };
+    let new_rounds = |rounds: Vec<Random>| Rounds {
+        info: info.clone(),
+        rounds,
+    };
+
+    let primary = new_rounds(vec![new_random(1, 1), new_random(2, 2), new_random(3, 3)]);
+    let secondary = new_rounds(vec![new_random(1, 1), new_random(2, 9)]);
+    let mut client = CrossCheck::new(primary, secondary).unwrap();
+
+    assert_eq!(client.to_info().unwrap(), info);
+    assert_eq!(client.get(Some(1)).unwrap(), new_random(1, 1));
+    assert!(matches!(client.get(Some(2)), Err(Error::Equivocation(_, _))));
+    // latest round of primary is not with secondary.
+    assert!(matches!(client.get(None), Err(Error::Invalid(_, _))));
+
+    let items: Vec<Result<Random>> = client.watch().unwrap().collect();
+    assert_eq!(items.len(), 3);
+    assert_eq!(items[0].as_ref().unwrap(), &new_random(1, 1));
+    assert!(matches!(items[1], Err(Error::Equivocation(_, _))));
+    assert!(items[2].is_err());
+
+    // chain info must match.
+    let (primary, _) = client.into_inner();
+    let other = Rounds {
+        info: Info {
+            hash: ChainHash([2; 32]),
+            ..Info::default()
+        },
+        rounds: vec![],
+    };
+    assert!(matches!(CrossCheck::new(primary, other), Err(Error::NotSecure(_, _))));
+}
+
+#[test]
+fn test_cross_check_late() {
+    let info = Info {
+        hash: ChainHash([1; 32]),
+        period: time::Duration::from_secs(30),
+        ..Info::default()
+    };
+    let new_client = |pending: usize| {
+        let new_rounds = || Rounds {
+            info: info.clone(),
+            rounds: vec![new_random(1, 1), new_random(2, 2)],
+        };
+        let late = Late {
+            rounds: new_rounds(),
+            pending,
+        };
+        let start = info.round_time(2).unwrap();
+        let clock = Arc::new(SimClock(Mutex::new(start)));
+        let mut client = CrossCheck::new(new_rounds(), late).unwrap();
+        client
+            .set_tolerance(time::Duration::from_secs(2))
+            .set_clock(Arc::clone(&clock) as Arc<dyn Clock>);
+        (client, clock, start)
+    };
+
+    // secondary catches up within tolerance, retried with backoff.
+    let (mut client, clock, start) = new_client(2);
+    assert_eq!(client.get(Some(2)).unwrap(), new_random(2, 2));
+    assert_eq!(clock.now(), start + time::Duration::from_millis(300));
+
+    // secondary does not catch up within tolerance.
+    let (mut client, clock, start) = new_client(100);
+    match client.get(Some(2)) {
+        Err(Error::RoundNotYetAvailable(_, 2, _)) => (),
+        res => panic!("unexpected {:?}", res),
+    }
+    assert_eq!(clock.now(), start + time::Duration::from_secs(2));
+}
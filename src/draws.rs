@@ -0,0 +1,132 @@
+//! Module implement helpers to convert randomness into human auditable
+//! forms, like dice rolls, card draws and BIP-39 word indices.
+//!
+//! All helpers are deterministic and can be re-derived by auditors with
+//! nothing more than a SHA-256 tool:
+//!
+//! * Byte stream, block `i` of the stream is the SHA-256 hash of the
+//!   round's randomness followed by `i` as 4 byte big-endian integer,
+//!   starting from `i = 0`. Bytes are consumed in order, block after block.
+//! * Unbiased integer in `[0, n)`, take the minimum number of bytes `k`
+//!   such that `256^k >= n`, read them as big-endian integer `x`. If
+//!   `x < 256^k - (256^k % n)` the result is `x % n`, otherwise discard `x`
+//!   and repeat with the next `k` bytes. For a six sided die, this means:
+//!   take one byte, discard it if it is 252 or above, else `byte % 6 + 1`.
+
+use sha2::{Digest, Sha256};
+
+use crate::Random;
+
+/// Deterministic byte stream, and unbiased integers, derived from a
+/// round of randomness. Refer module documentation for the algorithm.
+pub struct Draws {
+    seed: Vec<u8>,
+    counter: u32,
+    block: [u8; 32],
+    pos: usize,
+}
+
+impl Draws {
+    /// Create a new stream of draws from round `r`.
+    pub fn new(r: &Random) -> Draws {
+        let mut draws = Draws {
+            seed: r.randomness.as_bytes().to_vec(),
+            counter: 0,
+            block: [0; 32],
+            pos: 0,
+        };
+        draws.refill();
+        draws
+    }
+
+    /// Return the next byte from the stream.
+    pub fn next_byte(&mut self) -> u8 {
+        if self.pos >= self.block.len() {
+            self.counter = self.counter.wrapping_add(1);
+            self.refill();
+        }
+        let byte = self.block[self.pos];
+        self.pos += 1;
+        byte
+    }
+
+    /// Return an unbiased integer in the range `[0, n)`. Returns 0 if `n`
+    /// is 0 or 1.
+    pub fn uniform(&mut self, n: u32) -> u32 {
+        if n <= 1 {
+            return 0;
+        }
+
+        let (k, space): (usize, u64) = match n {
+            n if n <= 0x100 => (1, 0x100),
+            n if n <= 0x1_0000 => (2, 0x1_0000),
+            n if n <= 0x100_0000 => (3, 0x100_0000),
+            _ => (4, 0x1_0000_0000),
+        };
+        let limit = space - (space % (n as u64));
+        loop {
+            let x = (0..k).fold(0_u64, |x, _| (x << 8) | (self.next_byte() as u64));
+            if x < limit {
+                break (x % (n as u64)) as u32;
+            }
+        }
+    }
+
+    fn refill(&mut self) {
+        let mut hasher = Sha256::default();
+        hasher.update(&self.seed);
+        hasher.update(self.counter.to_be_bytes());
+        self.block.copy_from_slice(&hasher.finalize());
+        self.pos = 0;
+    }
+}
+
+/// Roll `n` dice, each with `sides` faces, returning values in the range
+/// `[1, sides]`.
+pub fn dice_rolls(r: &Random, sides: u32, n: usize) -> Vec<u32> {
+    let mut draws = Draws::new(r);
+    (0..n).map(|_| draws.uniform(sides) + 1).collect()
+}
+
+/// Draw `n` cards, without replacement, from a deck of `deck` cards
+/// numbered `0..deck`. Uses Fisher-Yates shuffle, where `i`th draw, for
+/// `i` starting from 0, swaps position `i` with position
+/// `i + uniform(deck - i)` and picks the card at position `i`.
+///
+/// Returns at most `deck` cards.
+pub fn card_draws(r: &Random, deck: usize, n: usize) -> Vec<usize> {
+    let mut draws = Draws::new(r);
+    let mut cards: Vec<usize> = (0..deck).collect();
+    let n = std::cmp::min(n, deck);
+    for i in 0..n {
+        let j = i + (draws.uniform((deck - i) as u32) as usize);
+        cards.swap(i, j);
+    }
+    cards.truncate(n);
+    cards
+}
+
+/// Return BIP-39 word indices, into the standard 2048 word list, treating
+/// the round's 32 byte randomness as 256 bits of entropy. Last word
+/// carries the 8 bit checksum, which is the first byte of the SHA-256
+/// hash of the randomness.
+pub fn bip39_indices(r: &Random) -> [u16; 24] {
+    let entropy = r.randomness.as_bytes();
+    let checksum = Sha256::digest(entropy)[0];
+
+    let mut bits = entropy.to_vec();
+    bits.push(checksum);
+
+    let mut indices = [0_u16; 24];
+    for (i, index) in indices.iter_mut().enumerate() {
+        for bit in (i * 11)..((i + 1) * 11) {
+            let set = (bits[bit / 8] >> (7 - (bit % 8))) & 1;
+            *index = (*index << 1) | (set as u16);
+        }
+    }
+    indices
+}
+
+#[cfg(test)]
+#[path = "draws_test.rs"]
+mod draws_test;
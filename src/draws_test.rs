@@ -0,0 +1,79 @@
+use crate::fixtures::new_random;
+
+use super::*;
+
+#[test]
+fn test_draws_stream() {
+    let r = new_random(1, 7);
+    let mut draws = Draws::new(&r);
+
+    let block0 = {
+        let mut hasher = Sha256::default();
+        hasher.update(&[7; 32]);
+        hasher.update(0_u32.to_be_bytes());
+        hasher.finalize().to_vec()
+    };
+    let block1 = {
+        let mut hasher = Sha256::default();
+        hasher.update(&[7; 32]);
+        hasher.update(1_u32.to_be_bytes());
+        hasher.finalize().to_vec()
+    };
+    let bytes: Vec<u8> = (0..64).map(|_| draws.next_byte()).collect();
+    assert_eq!(&bytes[..32], block0.as_slice());
+    assert_eq!(&bytes[32..], block1.as_slice());
+}
+
+#[test]
+fn test_dice_rolls() {
+    let r = new_random(1, 1);
+    let rolls = dice_rolls(&r, 6, 1000);
+    assert_eq!(rolls.len(), 1000);
+    assert!(rolls.iter().all(|x| (1..=6).contains(x)));
+    assert_eq!(rolls, dice_rolls(&r, 6, 1000));
+
+    let mut counts = [0; 6];
+    rolls.iter().for_each(|x| counts[(*x - 1) as usize] += 1);
+    assert!(counts.iter().all(|c| *c > 100), "{:?}", counts);
+
+    // hand derived, first byte below 252 gives the roll.
+    let mut draws = Draws::new(&r);
+    let roll = loop {
+        match draws.next_byte() {
+            b if b < 252 => break (b % 6) as u32 + 1,
+            _ => (),
+        }
+    };
+    assert_eq!(rolls[0], roll);
+
+    let rolls = dice_rolls(&r, 100_000, 100);
+    assert!(rolls.iter().all(|x| (1..=100_000).contains(x)));
+}
+
+#[test]
+fn test_card_draws() {
+    let r = new_random(1, 2);
+    let mut cards = card_draws(&r, 52, 52);
+    assert_eq!(cards, card_draws(&r, 52, 52));
+    assert_eq!(&card_draws(&r, 52, 5)[..], &cards[..5]);
+    cards.sort();
+    assert_eq!(cards, (0..52).collect::<Vec<usize>>());
+
+    assert_eq!(card_draws(&r, 3, 10).len(), 3);
+    assert_eq!(card_draws(&r, 0, 10).len(), 0);
+}
+
+#[test]
+fn test_bip39_indices() {
+    // BIP-39 test vector, 256 bit entropy of zeros is
+    // "abandon abandon ... abandon art".
+    let r = new_random(1, 0);
+    let mut indices = [0_u16; 24];
+    indices[23] = 102;
+    assert_eq!(bip39_indices(&r), indices);
+
+    let r = new_random(1, 0xff);
+    let indices = bip39_indices(&r);
+    assert!(indices[..23].iter().all(|x| *x == 2047));
+    assert!(indices.iter().all(|x| *x < 2048));
+}
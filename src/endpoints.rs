@@ -1,6 +1,33 @@
-use std::time;
+use tokio::sync::Semaphore;
 
-use crate::{client::Endpoint, core::MAX_CONNS, http::Http, Config, Error, Info, Random, Result};
+use std::{
+    cmp,
+    collections::BTreeMap,
+    ops,
+    sync::{
+        atomic::{AtomicU64, Ordering::SeqCst},
+        Arc,
+    },
+    time,
+};
+
+use crate::clock::{Clock, SystemClock};
+
+use crate::{
+    breaker::{Breaker, Circuit, CircuitState},
+    client::{Category, Endpoint},
+    core::MAX_CONNS,
+    events::{Event, Listener},
+    http::{CacheHeaders, EndpointStats, Http},
+    ipfilter::IpFilter,
+    statedir::{Samples, StateDir},
+    transport::SharedTransport,
+    verify, Agreement, BootPlan, BootReport, CatchUp, Config, EndpointCheck, Error, Hedge, Info,
+    Random, Result, ReverifyReport, SecurityPolicy, Strictness, Usage,
+};
+
+const BUDGET_WINDOW: time::Duration = time::Duration::from_secs(3600);
+const PERSIST_INTERVAL: time::Duration = time::Duration::from_secs(60);
 
 // State of each endpoint. An endpoint is booted and subsequently
 // used to watch/get future rounds of random-ness.
@@ -8,9 +35,18 @@ use crate::{client::Endpoint, core::MAX_CONNS, http::Http, Config, Error, Info,
 pub(crate) struct State {
     pub(crate) info: Info,
     pub(crate) check_point: Option<Random>,
-    pub(crate) determinism: bool,
+    pub(crate) policy: SecurityPolicy,
     pub(crate) secure: bool,
     pub(crate) max_conns: usize,
+    pub(crate) cache_historical: bool,
+    pub(crate) hedge: Option<Hedge>,
+    pub(crate) catch_up: CatchUp,
+    pub(crate) agreement: Agreement,
+    pub(crate) clock: Arc<dyn Clock>,
+    // gaps in the chain, accepted by the application.
+    pub(crate) gaps: Vec<ops::RangeInclusive<u128>>,
+    // requests left in the budget, for the request being served.
+    pub(crate) allowance: Option<Allowance>,
 }
 
 impl Default for State {
@@ -18,9 +54,16 @@ impl Default for State {
         State {
             info: Info::default(),
             check_point: None,
-            determinism: bool::default(),
+            policy: SecurityPolicy::default(),
             secure: bool::default(),
             max_conns: MAX_CONNS,
+            cache_historical: true,
+            hedge: None,
+            catch_up: CatchUp::default(),
+            agreement: Agreement::default(),
+            clock: Arc::new(SystemClock),
+            gaps: Vec::default(),
+            allowance: None,
         }
     }
 }
@@ -28,13 +71,54 @@ impl Default for State {
 impl From<Config> for State {
     fn from(mut cfg: Config) -> Self {
         State {
-            info: Info::default(),
+            info: cfg.info.take().unwrap_or_default(),
             check_point: cfg.check_point.take(),
-            determinism: cfg.determinism,
+            policy: cfg.policy,
             secure: cfg.secure,
             max_conns: cfg.max_conns,
+            cache_historical: cfg.cache_historical,
+            hedge: cfg.hedge.take(),
+            catch_up: cfg.catch_up,
+            agreement: cfg.agreement,
+            clock: cfg.clock,
+            gaps: Vec::default(),
+            allowance: None,
+        }
+    }
+}
+
+// Requests left in the budget for the current hour, refer
+// Config::request_budget. Shared by endpoints serving a request, so that
+// a single request catching up on the chain cannot exceed the budget.
+#[derive(Clone)]
+pub(crate) struct Allowance {
+    budget: u64,
+    left: Arc<AtomicU64>,
+}
+
+impl Allowance {
+    pub(crate) fn new(budget: u64, left: u64) -> Allowance {
+        Allowance {
+            budget,
+            left: Arc::new(AtomicU64::new(left)),
         }
     }
+
+    // return number of requests left, fail with BudgetExceeded if none.
+    pub(crate) fn to_left(&self) -> Result<u64> {
+        match self.left.load(SeqCst) {
+            0 => {
+                let prefix = format!("{}:{}", file!(), line!());
+                Err(Error::BudgetExceeded(prefix, self.budget))
+            }
+            left => Ok(left),
+        }
+    }
+
+    pub(crate) fn spend(&self, n: u64) {
+        let sub = |left: u64| Some(left.saturating_sub(n));
+        self.left.fetch_update(SeqCst, SeqCst, sub).ok();
+    }
 }
 
 // Endpoints is an enumeration of several known http endpoint from
@@ -43,38 +127,142 @@ pub(crate) struct Endpoints {
     name: String,
     state: State,
     endpoints: Vec<Inner>,
+    // whether state.info is validated with the drand-group.
+    validated: bool,
+    // global limit on concurrent requests, shared by all endpoints.
+    limit: Option<Arc<Semaphore>>,
+    // allowed remote addresses, shared by all endpoints.
+    filter: Option<Arc<IpFilter>>,
+    // shared with other clients.
+    transport: Option<SharedTransport>,
+    // applied to endpoints without their own http client.
+    proxy: Option<String>,
+    request_timeout: Option<time::Duration>,
+    // root of trust, when booted without chain-hash.
+    chain_hash: Option<Vec<u8>>,
+    // pick a chain by its period, refer Config::max_period.
+    max_period: Option<time::Duration>,
+    // circuit of each endpoint, keyed by its url. Endpoints with open
+    // circuit are left out of endpoint selection.
+    breaker: Breaker,
+    circuits: BTreeMap<String, Circuit>,
+    // latest round returned by this client.
+    latest_round: u128,
+    // watchdog, refer Config::stall_periods, and the latest round after
+    // which a stall was reported.
+    stall_periods: Option<u32>,
+    stalled: Option<u128>,
+    // requests per hour, refer Config::request_budget, along with start
+    // of the current hour, requests sent before it, and whether the
+    // budget was reported exceeded in this hour.
+    budget: Option<u64>,
+    budget_window: Option<(time::SystemTime, u64, bool)>,
+    // refer Config::recompute_randomness.
+    recompute: bool,
+    listeners: Vec<Listener>,
+    // persisted state, and latency samples loaded from it for endpoints
+    // yet to be added, along with the time state was last persisted.
+    state_dir: Option<StateDir>,
+    samples: Samples,
+    persisted: Option<time::SystemTime>,
 }
 
 impl Endpoints {
     pub(crate) fn from_config(name: &str, config: Config) -> Self {
-        Endpoints {
+        let state_dir = config.state_dir.clone().map(StateDir::new);
+        let (config, samples) = match state_dir.as_ref() {
+            Some(dir) => load_state(dir, config),
+            None => (config, Samples::default()),
+        };
+
+        let validated = config.info.is_none();
+        let transport = config.transport.clone();
+        let limit = match transport.as_ref() {
+            Some(transport) => transport.to_limit(),
+            None => config
+                .global_concurrency
+                .map(|n| Arc::new(Semaphore::new(n))),
+        };
+        let filter = config.ip_filter.clone().map(Arc::new);
+        let breaker = config.breaker.clone();
+        let proxy = config.proxy.clone();
+        let request_timeout = config.request_timeout;
+        let chain_hash = config.chain_hash.clone();
+        let max_period = config.max_period;
+        let endpoints = config.endpoints.clone();
+        let stall_periods = config.stall_periods;
+        let budget = config.request_budget;
+        let recompute = config.recompute_randomness;
+        let mut val = Endpoints {
             name: name.to_string(),
             state: config.into(),
             endpoints: Vec::default(),
+            validated,
+            limit,
+            filter,
+            transport,
+            proxy,
+            request_timeout,
+            chain_hash,
+            max_period,
+            breaker,
+            circuits: BTreeMap::default(),
+            latest_round: 0,
+            stall_periods,
+            stalled: None,
+            budget,
+            budget_window: None,
+            recompute,
+            listeners: Vec::default(),
+            state_dir,
+            samples,
+            persisted: None,
+        };
+        for endp in endpoints.into_iter() {
+            val.add_endpoint(endp, None);
         }
+        val
     }
 
-    pub(crate) fn add_endpoint(&mut self, endp: Endpoint) -> &mut Self {
+    pub(crate) fn add_endpoint(
+        &mut self,
+        endp: Endpoint,
+        client: Option<reqwest::Client>,
+    ) -> &mut Self {
         let name = self.name.to_string();
-        let endp = match endp {
-            Endpoint::HttpDrandApi => {
-                let endp = Http::new_drand_api();
-                Inner::Http { name, endp }
-            }
-            Endpoint::HttpDrandApi2 => {
-                let endp = Http::new_drand_api();
-                Inner::Http { name, endp }
-            }
-            Endpoint::HttpDrandApi3 => {
-                let endp = Http::new_drand_api();
-                Inner::Http { name, endp }
-            }
-            Endpoint::HttpCloudflare => {
-                let endp = Http::new_drand_api();
-                Inner::Http { name, endp }
+        let mut endp = match endp {
+            Endpoint::HttpDrandApi => Http::new_drand_api(),
+            Endpoint::HttpDrandApi2 => Http::new_drand_api2(),
+            Endpoint::HttpDrandApi3 => Http::new_drand_api3(),
+            Endpoint::HttpCloudflare => Http::new_cloudflare(),
+            Endpoint::Http { url, category } => Http::new(&url, category),
+        };
+        // client built by shared transport is not specific to this client,
+        // user agent is sent with each request.
+        let client = match (client, self.transport.as_ref()) {
+            (Some(client), _) => Some(client),
+            (None, Some(transport)) => {
+                endp.set_user_agent(self.user_agent());
+                Some(transport.to_client())
             }
+            (None, None) => None,
         };
-        self.endpoints.push(endp);
+        if let Some(transport) = self.transport.as_ref() {
+            if let Ok(stats) = transport.to_stats(&endp.to_base_url()) {
+                endp.set_stats(stats);
+            }
+        }
+        if let Some(samples) = self.samples.get(&endp.to_base_url()) {
+            endp.seed_stats(samples);
+        }
+        endp.set_limit(self.limit.clone())
+            .set_ip_filter(self.filter.clone())
+            .set_clock(Arc::clone(&self.state.clock))
+            .set_proxy(self.proxy.clone())
+            .set_request_timeout(self.request_timeout)
+            .set_recompute_randomness(self.recompute)
+            .set_client(client);
+        self.endpoints.push(Inner::Http { name, endp });
         self
     }
 
@@ -82,10 +270,27 @@ impl Endpoints {
         self.state.info.clone()
     }
 
+    pub(crate) fn to_check_point(&self) -> Option<Random> {
+        self.state.check_point.clone()
+    }
+
     pub(crate) async fn boot(&mut self, chain_hash: Option<Vec<u8>>) -> Result<()> {
         let agent = self.user_agent();
+        let chain_hash = self.to_root_of_trust(chain_hash).await?;
         // root of trust.
         let rot = chain_hash.as_ref().map(|x| x.as_slice());
+
+        // instant boot, supplied info is validated on first network call.
+        if !self.validated && self.state.check_point.is_some() {
+            return match rot {
+                Some(rot) if rot != self.state.info.hash.as_bytes() => {
+                    let msg = format!("not expected drand-group");
+                    err_at!(NotSecure, msg: msg)
+                }
+                _ => Ok(()),
+            };
+        }
+
         let (info, latest) = match self.endpoints.len() {
             0 => err_at!(Invalid, msg: format!("initialize endpoint"))?,
             1 => self.endpoints[0].boot_phase1(rot, agent.clone()).await?,
@@ -95,75 +300,341 @@ impl Endpoints {
                     endp.boot_phase1(rot, agent.clone()).await?
                 };
 
+                // tail endpoints are validated on detached copies, only
+                // validated endpoints carry their latency stats, rest of
+                // them are left out of endpoint selection.
                 let mut tail = vec![];
-                for mut endp in self.endpoints[1..].to_vec() {
+                for mut endp in self.endpoints[1..].iter().map(Inner::to_detached) {
                     let (info1, latest1) = (info.clone(), latest.clone());
-                    tail.push(async {
-                        let (info2, _) = {
-                            let agent = agent.clone();
-                            endp.boot_phase1(rot, agent).await?
-                        };
-
-                        Self::boot_validate_info(info1, info2)?;
-
-                        let s = {
-                            let mut s = State::default();
-                            s.check_point = None;
-                            s.secure = false;
-                            s
-                        };
-                        let (_, r) = {
-                            let round = Some(latest1.round);
-                            endp.get(s, round, agent.clone()).await?
-                        };
-                        Self::boot_validate_latest(latest1, r)?;
-
-                        Ok::<Inner, Error>(endp)
+                    let agent = agent.clone();
+                    tail.push(async move {
+                        let res = async {
+                            let (info2, _) = endp.boot_phase1(rot, agent.clone()).await?;
+                            Self::boot_validate_info(info1, info2)?;
+
+                            let s = {
+                                let mut s = State::default();
+                                s.check_point = None;
+                                s.secure = false;
+                                s
+                            };
+                            let (_, r) = endp.get(s, Some(latest1.round), agent).await?;
+                            Self::boot_validate_latest(latest1, r)
+                        }
+                        .await;
+                        (endp, res)
                     })
                 }
 
-                futures::future::join_all(tail).await;
+                let items = futures::future::join_all(tail).await;
+                for (i, (endp, res)) in items.into_iter().enumerate() {
+                    self.endpoints[i + 1].fold_detached(endp, res.is_ok());
+                }
 
                 (info, latest)
             }
         };
 
+        if !self.validated {
+            Self::boot_validate_info(self.state.info.clone(), info.clone())?;
+            self.validated = true;
+        }
+
         self.state.info = info;
         self.state = {
             let s = self.state.clone();
-            self.endpoints[0]
-                .boot_phase2(s, latest, agent.clone())
-                .await?
+            let i = self.to_catch_up_index();
+            let mut res = self.endpoints[i]
+                .boot_phase2(s.clone(), latest.clone(), agent.clone())
+                .await;
+            // rounds missing with catch-up endpoint, try other endpoints.
+            for j in (0..self.endpoints.len()).filter(|j| *j != i) {
+                match &res {
+                    Err(Error::MissingRounds(_, _)) => (),
+                    _ => break,
+                }
+                res = self.endpoints[j]
+                    .boot_phase2(s.clone(), latest.clone(), agent.clone())
+                    .await;
+            }
+            match &res {
+                Err(Error::NotSecure(_, msg)) => {
+                    let msg = msg.to_string();
+                    self.emit(Event::VerificationFailure { round: None, msg });
+                }
+                Err(Error::Verify(_, err)) => {
+                    let (round, msg) = (Some(err.to_round()), err.to_string());
+                    self.emit(Event::VerificationFailure { round, msg });
+                }
+                _ => (),
+            }
+            res?
         };
+        if let Some(check_point) = self.state.check_point.as_ref() {
+            self.emit(Event::CheckPoint(check_point.clone()));
+        }
+        self.persist(true);
 
         Ok(())
     }
 
+    // chain-hash to boot with, supplied by the caller, or configured, or
+    // else picked by its period. Picked chain is remembered for subsequent
+    // boots. Supplied info, if it meets max_period, avoids a network call.
+    async fn to_root_of_trust(&mut self, chain_hash: Option<Vec<u8>>) -> Result<Option<Vec<u8>>> {
+        let max_period = match (chain_hash.or_else(|| self.chain_hash.clone()), self.max_period) {
+            (Some(chain_hash), _) => return Ok(Some(chain_hash)),
+            (None, None) => return Ok(None),
+            (None, Some(max_period)) => max_period,
+        };
+        let info = &self.state.info;
+        if *info != Info::default() && info.period <= max_period {
+            return Ok(Some(info.hash.as_bytes().to_vec()));
+        }
+
+        let agent = self.user_agent();
+        let (infos, skipped) = match self.endpoints.first_mut() {
+            Some(endp) => endp.to_chains(agent).await?,
+            None => err_at!(Invalid, msg: format!("initialize endpoint"))?,
+        };
+        for (hash, err) in skipped.into_iter() {
+            self.emit(Event::ChainSkipped {
+                hash,
+                msg: err.to_string(),
+            });
+        }
+        // shortest period, older chain on a tie.
+        let info = infos
+            .into_iter()
+            .filter(|info| info.period <= max_period)
+            .min_by_key(|info| (info.period, info.genesis_time));
+        match info {
+            Some(info) => {
+                let chain_hash = info.hash.as_bytes().to_vec();
+                self.chain_hash = Some(chain_hash.clone());
+                Ok(Some(chain_hash))
+            }
+            None => err_at!(Invalid, msg: format!("no chain with period <= {:?}", max_period)),
+        }
+    }
+
+    // check every endpoint for reachability, chain info agreement, and
+    // freshness of latest round. Endpoints are checked on detached copies,
+    // so that their latency stats and circuits are left untouched.
+    pub(crate) async fn validate_endpoints(&self) -> Vec<EndpointCheck> {
+        let agent = self.user_agent();
+        let rot = self.chain_hash.as_ref().map(|x| x.as_slice());
+
+        let futs = self.endpoints.iter().map(|endp| {
+            let (mut endp, agent) = (endp.to_detached(), agent.clone());
+            async move {
+                let start = time::Instant::now();
+                let res = endp.boot_phase1(rot, agent).await;
+                (endp, res, start.elapsed())
+            }
+        });
+        let results = futures::future::join_all(futs).await;
+
+        // compare with client's info, or else with the first reachable
+        // endpoint.
+        let reference = match &self.state.info {
+            info if *info != Info::default() => Some(info.clone()),
+            _ => results.iter().find_map(|(_, res, _)| res.as_ref().ok().map(|x| x.0.clone())),
+        };
+        let now = self.state.clock.now();
+
+        let iter = results.into_iter().map(|(endp, res, latency)| {
+            let mut check = EndpointCheck {
+                url: endp.to_url(),
+                category: endp.to_category(),
+                info: None,
+                latest: None,
+                lag: None,
+                latency,
+                error: None,
+            };
+            let (info, latest) = match res {
+                Ok(val) => val,
+                Err(err) => {
+                    check.error = Some(err.to_string());
+                    return check;
+                }
+            };
+            let lag = info.round_at(now).map(|r| r.saturating_sub(latest.round));
+            let agrees = match reference.as_ref() {
+                Some(reference) => reference.validate(&info, Strictness::Full),
+                None => Ok(()),
+            };
+            check.error = match (agrees, lag.as_ref()) {
+                (Err(err), _) => Some(err.to_string()),
+                (Ok(()), Err(err)) => Some(err.to_string()),
+                // one round may be in flight.
+                (Ok(()), Ok(lag)) if *lag > 1 => {
+                    let msg = format!("latest round {} is {} rounds behind", latest.round, lag);
+                    Some(msg)
+                }
+                (Ok(()), Ok(_)) => None,
+            };
+            check.info = Some(info);
+            check.latest = Some(latest.round);
+            check.lag = lag.ok();
+            check
+        });
+        iter.collect()
+    }
+
+    // fetch info and latest round from catch-up endpoint, and estimate the
+    // cost of booting with configured security policy.
+    pub(crate) async fn plan_boot(&mut self, chain_hash: Option<Vec<u8>>) -> Result<BootPlan> {
+        let agent = self.user_agent();
+        let chain_hash = self.to_root_of_trust(chain_hash).await?;
+        let rot = chain_hash.as_ref().map(|x| x.as_slice());
+
+        if self.endpoints.is_empty() {
+            err_at!(Invalid, msg: format!("initialize endpoint"))?
+        }
+        let i = self.to_catch_up_index();
+        let (info, latest) = self.endpoints[i].boot_phase1(rot, agent).await?;
+        if !self.validated {
+            Self::boot_validate_info(self.state.info.clone(), info.clone())?;
+        }
+
+        let mut state = self.state.clone();
+        state.info = info;
+        self.endpoints[i].plan(state, &latest)
+    }
+
+    // boot with configured security policy, if that does not complete within
+    // the deadline, abandon it and boot with SecurityPolicy::Assumed.
+    pub(crate) async fn boot_with_deadline(
+        &mut self,
+        chain_hash: Option<Vec<u8>>,
+        deadline: time::Duration,
+    ) -> Result<BootReport> {
+        use futures::future::{select, Either};
+
+        let start = time::Instant::now();
+        let requested = self.state.policy.clone();
+        // an abandoned boot may have validated and replaced info, that is
+        // undone on deadline.
+        let (validated, state) = (self.validated, self.state.clone());
+
+        let res = {
+            let fut = self.boot(chain_hash.clone());
+            futures::pin_mut!(fut);
+            match select(fut, futures_timer::Delay::new(deadline)).await {
+                Either::Left((res, _)) => Some(res),
+                Either::Right(_) => None,
+            }
+        };
+
+        match res {
+            Some(res) => res?,
+            None => {
+                self.validated = validated;
+                self.state = state;
+                if requested == SecurityPolicy::Assumed {
+                    let msg = format!("boot deadline {:?} exceeded", deadline);
+                    err_at!(Timeout, msg: msg)?
+                }
+                self.state.policy = SecurityPolicy::Assumed;
+                self.boot(chain_hash).await?
+            }
+        };
+
+        Ok(BootReport {
+            requested,
+            policy: self.state.policy.clone(),
+            elapsed: start.elapsed(),
+            check_point: self.state.check_point.as_ref().map(|r| r.round),
+        })
+    }
+
     pub(crate) async fn get(&mut self, round: Option<u128>) -> Result<Random> {
+        let mut allowance = self.check_budget()?;
+        if !self.validated {
+            self.boot_lazy().await?;
+            allowance = self.check_budget()?;
+        }
+        // requests left in the budget are shared by endpoints serving
+        // this request, refer Http::verify.
+        let req = State {
+            allowance,
+            ..self.state.clone()
+        };
+
         let agent = self.user_agent();
+        let category = match round {
+            Some(round) if self.state.cache_historical && self.is_historical(round) => {
+                Category::Cache
+            }
+            _ => Category::Origin,
+        };
         let (state, r) = loop {
-            match self.get_endpoint_pair() {
+            match self.get_endpoint_pair(category) {
                 (Some(mut e1), Some(mut e2)) => {
-                    let (res1, res2) = futures::join!(
-                        e1.get(self.state.clone(), round, agent.clone()),
-                        e2.get(self.state.clone(), round, agent.clone()),
-                    );
+                    let (res1, res2) = match self.to_hedge_after(&e1) {
+                        Some(after) => {
+                            let (s, a) = (req.clone(), agent.clone());
+                            hedged_get(&mut e1, &mut e2, s, round, a, after).await
+                        }
+                        None => {
+                            let (res1, res2) = futures::join!(
+                                e1.get(req.clone(), round, agent.clone()),
+                                e2.get(req.clone(), round, agent.clone()),
+                            );
+                            (Some(res1), Some(res2))
+                        }
+                    };
+                    if let Some(res1) = res1.as_ref() {
+                        self.put_endpoint(&e1, res1, round);
+                    }
+                    if let Some(res2) = res2.as_ref() {
+                        self.put_endpoint(&e2, res2, round);
+                    }
+
                     match (res1, res2) {
-                        (Ok((s1, r1)), Ok((s2, r2))) => {
-                            if r1.round > r2.round {
-                                break (s1, r1);
-                            } else {
-                                break (s2, r2);
-                            };
+                        (Some(Ok(val1)), Some(Ok(val2))) => {
+                            break self.reconcile(&e1, val1, &e2, val2)?;
                         }
-                        (Ok((s1, r1)), Err(_)) => break (s1, r1),
-                        (Err(_), Ok((s2, r2))) => break (s2, r2),
-                        (Err(_), Err(_)) => (),
+                        (Some(Ok((s1, r1))), res2) => {
+                            if res2.is_some() {
+                                let (from, to) = (e2.to_url(), e1.to_url());
+                                self.emit(Event::Failover { from, to });
+                            }
+                            break (s1, r1);
+                        }
+                        (res1, Some(Ok((s2, r2)))) => {
+                            if res1.is_some() {
+                                let (from, to) = (e1.to_url(), e2.to_url());
+                                self.emit(Event::Failover { from, to });
+                            }
+                            break (s2, r2);
+                        }
+                        // no point in retrying on other endpoints, once the
+                        // budget is exhausted.
+                        (Some(Err(err @ Error::BudgetExceeded(_, _))), _)
+                        | (_, Some(Err(err @ Error::BudgetExceeded(_, _)))) => return Err(err),
+                        // no point in retrying, when both are missing the
+                        // round, or the round is not yet available. These
+                        // are not breaker failures, retrying would loop.
+                        (Some(Err(err1)), Some(Err(err2)))
+                            if is_round_error(&err1) && is_round_error(&err2) =>
+                        {
+                            return Err(err1)
+                        }
+                        (Some(Err(err)), None) | (None, Some(Err(err)))
+                            if is_round_error(&err) =>
+                        {
+                            return Err(err)
+                        }
+                        (_, _) => (),
                     };
                 }
                 (Some(mut e1), None) => {
-                    let state = self.state.clone();
-                    let (state, r) = e1.get(state, round, agent).await?;
+                    let res = e1.get(req.clone(), round, agent).await;
+                    self.put_endpoint(&e1, &res, round);
+                    let (state, r) = res?;
                     break (state, r);
                 }
                 (None, _) => {
@@ -172,27 +643,218 @@ impl Endpoints {
                 }
             }
         };
-        self.state = state;
+
+        let old = self.state.check_point.as_ref().map(|r| r.round);
+        let advanced = match state.check_point.as_ref() {
+            Some(check_point) if Some(check_point.round) > old => {
+                self.emit(Event::CheckPoint(check_point.clone()));
+                true
+            }
+            _ => false,
+        };
+        self.state = State {
+            allowance: None,
+            ..state
+        };
+        if advanced {
+            self.persist(false);
+        }
+
+        if r.round > self.latest_round {
+            self.latest_round = r.round;
+            self.emit(Event::NewRound(r.clone()));
+        }
+        if round.is_none() {
+            self.check_stall()?;
+        }
 
         Ok(r)
     }
+
+    // return ChainStalled error, if no new round is observed for
+    // `stall_periods` periods since the latest observed round. Event is
+    // emitted once for each stall.
+    pub(crate) fn check_stall(&mut self) -> Result<()> {
+        let k = match self.stall_periods {
+            Some(k) => k,
+            None => return Ok(()),
+        };
+        let cp = self.state.check_point.as_ref().map(|r| r.round);
+        let round = cmp::max(self.latest_round, cp.unwrap_or_default());
+        if round == 0 {
+            return Ok(());
+        }
+
+        let (info, now) = (&self.state.info, self.state.clock.now());
+        let elapsed = now.duration_since(info.round_time(round)?).unwrap_or_default();
+        match info.period.checked_mul(k) {
+            Some(limit) if elapsed >= limit => {
+                if self.stalled != Some(round) {
+                    self.stalled = Some(round);
+                    self.emit(Event::ChainStalled { round, elapsed });
+                }
+                let prefix = format!("{}:{}", file!(), line!());
+                Err(Error::ChainStalled(prefix, round, elapsed))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // return BudgetExceeded error, if requests sent in the current hour
+    // exhaust the budget. Event is emitted once for the hour. Return the
+    // requests left, to be handed over to endpoints via State::allowance.
+    pub(crate) fn check_budget(&mut self) -> Result<Option<Allowance>> {
+        let budget = match self.budget {
+            Some(budget) => budget,
+            None => return Ok(None),
+        };
+        let (now, requests) = (self.state.clock.now(), self.to_usage().requests);
+        let (start, before, reported) = match self.budget_window {
+            Some((start, before, reported)) => match now.duration_since(start) {
+                Ok(elapsed) if elapsed >= BUDGET_WINDOW => (now, requests, false),
+                _ => (start, before, reported),
+            },
+            None => (now, requests, false),
+        };
+
+        let used = requests.saturating_sub(before);
+        let exceeded = used >= budget;
+        self.budget_window = Some((start, before, reported || exceeded));
+        match exceeded {
+            true => {
+                if !reported {
+                    self.emit(Event::BudgetExceeded { requests: used, budget });
+                }
+                let prefix = format!("{}:{}", file!(), line!());
+                Err(Error::BudgetExceeded(prefix, budget))
+            }
+            false => Ok(Some(Allowance::new(budget, budget - used))),
+        }
+    }
+
+    pub(crate) fn to_usage(&self) -> Usage {
+        let mut usage = Usage::default();
+        for endp in self.endpoints.iter() {
+            let timings = endp.to_endpoint_stats().timings;
+            usage.requests = usage.requests.saturating_add(timings.requests);
+            usage.bytes = usage.bytes.saturating_add(timings.bytes);
+            usage.verified = usage.verified.saturating_add(timings.verified);
+        }
+        usage
+    }
+
+    // cache headers from the most recent response for latest round.
+    pub(crate) fn to_cache_headers(&self) -> Option<(time::Instant, CacheHeaders)> {
+        let iter = self.endpoints.iter().filter_map(|e| e.to_cache_headers());
+        iter.max_by_key(|(at, _)| *at)
+    }
+
+    pub(crate) fn to_endpoint_stats(&self) -> Vec<EndpointStats> {
+        let iter = self.endpoints.iter().map(|endp| {
+            let mut stats = endp.to_endpoint_stats();
+            stats.circuit = self.to_circuit_state(&stats.url);
+            stats.quarantined = stats.circuit == CircuitState::Open;
+            stats
+        });
+        iter.collect()
+    }
+
+    pub(crate) fn accept_gap(&mut self, rounds: ops::RangeInclusive<u128>) -> &mut Self {
+        self.state.gaps.push(rounds);
+        self
+    }
+
+    pub(crate) async fn reverify(&mut self, from: u128, till: u128) -> Result<ReverifyReport> {
+        if from == 0 || from > till {
+            err_at!(Invalid, msg: format!("invalid range {}..={}", from, till))?
+        }
+        let mut allowance = self.check_budget()?;
+        if !self.validated {
+            self.boot_lazy().await?;
+            allowance = self.check_budget()?;
+        }
+        let req = State {
+            allowance,
+            ..self.state.clone()
+        };
+
+        let agent = self.user_agent();
+        let mut endp = match self.get_endpoint_pair(Category::Cache) {
+            (Some(endp), _) => endp,
+            (None, _) => err_at!(IOError, msg: format!("missing/exhausted endpoint"))?,
+        };
+        let res = endp.reverify(&req, from, till, agent).await;
+        self.put_endpoint(&endp, &res, None);
+
+        let report = res?;
+        for (round, msg) in report.failures.iter() {
+            let (round, msg) = (Some(*round), msg.to_string());
+            self.emit(Event::VerificationFailure { round, msg });
+        }
+        Ok(report)
+    }
+
+    pub(crate) fn add_listener(&mut self, listener: Listener) -> &mut Self {
+        self.listeners.push(listener);
+        self
+    }
+
+    pub(crate) fn emit(&self, event: Event) {
+        for listener in self.listeners.iter() {
+            listener(&event)
+        }
+    }
 }
 
 impl Endpoints {
-    fn boot_validate_info(this: Info, other: Info) -> Result<()> {
-        if this.public_key != other.public_key {
-            let x = hex::encode(&this.public_key);
-            let y = hex::encode(&other.public_key);
-            err_at!(NotSecure, msg: format!("public-key {} ! {}", x, y))
-        } else if this.hash != other.hash {
-            let x = hex::encode(&this.hash);
-            let y = hex::encode(&other.hash);
-            err_at!(NotSecure, msg: format!("hash {} != {}", x, y))
-        } else {
-            Ok(())
+    // validate supplied info with every endpoint, endpoints that failed to
+    // respond are left out of endpoint selection.
+    async fn boot_lazy(&mut self) -> Result<()> {
+        let agent = self.user_agent();
+        let info = self.state.info.clone();
+
+        // endpoints are validated on detached copies, refer boot().
+        let mut items = vec![];
+        for mut endp in self.endpoints.iter().map(Inner::to_detached) {
+            let (info1, agent) = (info.clone(), agent.clone());
+            items.push(async move {
+                let res = async {
+                    let rot = Some(info1.hash.as_bytes());
+                    let (info2, _) = endp.boot_phase1(rot, agent).await?;
+                    Self::boot_validate_info(info1, info2)
+                }
+                .await;
+                (endp, res)
+            })
+        }
+
+        let (mut n, mut not_secure) = (0, None);
+        let items = futures::future::join_all(items).await;
+        for (i, (endp, res)) in items.into_iter().enumerate() {
+            self.endpoints[i].fold_detached(endp, res.is_ok());
+            match res {
+                Ok(()) => n += 1,
+                Err(err @ Error::NotSecure(_, _)) => not_secure = not_secure.or(Some(err)),
+                Err(_) => (),
+            }
+        }
+        if let Some(err) = not_secure {
+            return Err(err);
+        }
+
+        match n {
+            0 => err_at!(IOError, msg: format!("missing/exhausted endpoint")),
+            _ => {
+                self.validated = true;
+                Ok(())
+            }
         }
     }
 
+    fn boot_validate_info(this: Info, other: Info) -> Result<()> {
+        this.validate(&other, Strictness::KeysHash)
+    }
+
     fn boot_validate_latest(this: Random, other: Random) -> Result<()> {
         if this.round != other.round {
             err_at!(
@@ -216,14 +878,61 @@ impl Endpoints {
         }
     }
 
-    fn get_endpoint_pair(&self) -> (Option<Inner>, Option<Inner>) {
-        use crate::http::MAX_ELAPSED;
+    // historical rounds are those that are already generated by the
+    // drand-group.
+    fn is_historical(&self, round: u128) -> bool {
+        match self.state.info.round_at(self.state.clock.now()) {
+            Ok(latest) => round < latest,
+            Err(_) => false,
+        }
+    }
+
+    fn to_circuit_state(&self, url: &str) -> CircuitState {
+        match self.circuits.get(url) {
+            Some(circuit) => circuit.to_state(&self.breaker, self.state.clock.now()),
+            None => CircuitState::Closed,
+        }
+    }
 
+    // whether endpoint is paused, after it rate limited requests.
+    fn is_paused(&self, url: &str) -> bool {
+        match self.circuits.get(url) {
+            Some(circuit) => circuit.is_paused(self.state.clock.now()),
+            None => false,
+        }
+    }
+
+    // index of the endpoint to be used for catching up with latest round.
+    fn to_catch_up_index(&self) -> usize {
+        let mut iter = self.endpoints.iter().enumerate();
+        let item = iter.find(|(_, endp)| {
+            self.state.cache_historical
+                && endp.to_category() == Category::Cache
+                && self.to_circuit_state(&endp.to_url()) == CircuitState::Closed
+                && !self.is_paused(&endp.to_url())
+        });
+        match item {
+            Some((i, _)) => i,
+            None => 0,
+        }
+    }
+
+    // pick two best endpoints, preferring endpoints of `category`, and
+    // endpoints with closed circuit over half-open ones. Paused endpoints
+    // are left out.
+    fn get_endpoint_pair(&self, category: Category) -> (Option<Inner>, Option<Inner>) {
         let mut endpoints = vec![];
         for (i, endp) in self.endpoints.iter().enumerate() {
-            if endp.to_elapsed() < MAX_ELAPSED {
-                endpoints.push((i, endp.to_elapsed()));
+            if self.is_paused(&endp.to_url()) {
+                continue;
             }
+            let probe = match self.to_circuit_state(&endp.to_url()) {
+                CircuitState::Closed => false,
+                CircuitState::HalfOpen => true,
+                CircuitState::Open => continue,
+            };
+            let other = endp.to_category() != category;
+            endpoints.push((i, (probe, other, endp.to_elapsed())));
         }
         endpoints.sort_by(|x, y| x.1.cmp(&y.1));
 
@@ -243,6 +952,148 @@ impl Endpoints {
         }
     }
 
+    // hedge threshold for requests to `endp`.
+    fn to_hedge_after(&self, endp: &Inner) -> Option<time::Duration> {
+        match self.state.hedge.as_ref()? {
+            Hedge::Percentile(p) => endp.to_percentile(*p),
+            Hedge::After(after) => Some(*after),
+        }
+    }
+
+    // pick one of the responses from `e1` and `e2`, as per configured
+    // agreement policy.
+    fn reconcile(
+        &self,
+        e1: &Inner,
+        val1: (State, Random),
+        e2: &Inner,
+        val2: (State, Random),
+    ) -> Result<(State, Random)> {
+        let (r1, r2) = (&val1.1, &val2.1);
+
+        if r1.round == r2.round && r1 != r2 {
+            let msg = format!(
+                "round {} differs between {} and {}",
+                r1.round,
+                e1.to_url(),
+                e2.to_url()
+            );
+            let (round, m) = (Some(r1.round), msg.clone());
+            self.emit(Event::VerificationFailure { round, msg: m });
+            err_at!(Equivocation, msg: msg)?
+        }
+
+        match self.state.agreement {
+            Agreement::PreferHigher if r1.round > r2.round => Ok(val1),
+            Agreement::PreferHigher => Ok(val2),
+            Agreement::RequireMatch if r1.round == r2.round => Ok(val1),
+            Agreement::RequireMatch => {
+                let msg = format!(
+                    "latest round {} from {} != {} from {}",
+                    r1.round,
+                    e1.to_url(),
+                    r2.round,
+                    e2.to_url()
+                );
+                err_at!(Invalid, msg: msg)
+            }
+            Agreement::PreferVerified => {
+                let pk = &self.state.info.public_key;
+                let ok1 = verify::verify_chain(pk, &r1.previous_signature, r1).is_ok();
+                let ok2 = verify::verify_chain(pk, &r2.previous_signature, r2).is_ok();
+                for (ok, endp, r) in vec![(ok1, e1, r1), (ok2, e2, r2)].into_iter() {
+                    if !ok {
+                        let msg = format!("{} round {} not verified", endp.to_url(), r.round);
+                        let round = Some(r.round);
+                        self.emit(Event::VerificationFailure { round, msg });
+                    }
+                }
+                match (ok1, ok2) {
+                    (true, true) if r1.round > r2.round => Ok(val1),
+                    (true, true) => Ok(val2),
+                    (true, false) => Ok(val1),
+                    (false, true) => Ok(val2),
+                    (false, false) => {
+                        let msg = format!("rounds {} and {} not verified", r1.round, r2.round);
+                        err_at!(NotSecure, msg: msg)
+                    }
+                }
+            }
+        }
+    }
+
+    // update endpoint selected for a request, with its latest stats and
+    // the outcome of the request.
+    fn put_endpoint<T>(&mut self, endp: &Inner, res: &Result<T>, round: Option<u128>) {
+        let url = endp.to_url();
+        if let Some(item) = self.endpoints.iter_mut().find(|e| e.to_url() == url) {
+            *item = endp.clone();
+        }
+
+        let (breaker, now) = (&self.breaker, self.state.clock.now());
+        let circuit = self.circuits.entry(url.clone()).or_default();
+        let opened = match res {
+            Ok(_) => {
+                circuit.on_success(breaker, now);
+                false
+            }
+            Err(err) if is_round_error(err) => false,
+            Err(Error::RateLimited(_, _)) => false,
+            Err(Error::BudgetExceeded(_, _)) => false,
+            Err(_) => circuit.on_failure(breaker, now),
+        };
+        if let Err(Error::RateLimited(_, after)) = res {
+            let pause = after.unwrap_or(breaker.cool_off);
+            circuit.pause(now, pause);
+            self.emit(Event::RateLimited { url: url.clone(), pause });
+        }
+        if opened {
+            self.emit(Event::Quarantine(url));
+        }
+        if let Err(Error::BudgetExceeded(_, _)) = res {
+            // budget exhausted while serving the request, emit event.
+            self.check_budget().ok();
+        }
+
+        match res {
+            Err(Error::NotSecure(_, msg)) => {
+                let msg = msg.to_string();
+                self.emit(Event::VerificationFailure { round, msg });
+            }
+            Err(Error::Verify(_, err)) => {
+                let (round, msg) = (Some(err.to_round()), err.to_string());
+                self.emit(Event::VerificationFailure { round, msg });
+            }
+            _ => (),
+        }
+    }
+
+    // persist chain info, check_point and latency stats, if configured.
+    // Unless `force`d, state is persisted at most once in PERSIST_INTERVAL.
+    // Best effort, failure is emitted as event and state is persisted
+    // again on the next update.
+    fn persist(&mut self, force: bool) {
+        let dir = match self.state_dir.as_ref() {
+            Some(dir) => dir,
+            None => return,
+        };
+        let now = self.state.clock.now();
+        let due = match self.persisted.map(|at| now.duration_since(at)) {
+            Some(Ok(elapsed)) => force || elapsed >= PERSIST_INTERVAL,
+            Some(Err(_)) | None => true,
+        };
+        if !due {
+            return;
+        }
+
+        let iter = self.endpoints.iter().map(|e| (e.to_url(), e.to_samples()));
+        let stats: Samples = iter.collect();
+        match dir.save(&self.state.info, self.state.check_point.as_ref(), &stats) {
+            Ok(()) => self.persisted = Some(now),
+            Err(err) => self.emit(Event::PersistFailure { msg: err.to_string() }),
+        }
+    }
+
     fn user_agent(&self) -> Option<reqwest::header::HeaderValue> {
         use reqwest::header::HeaderValue;
 
@@ -267,6 +1118,21 @@ impl Inner {
         }
     }
 
+    async fn to_chains(
+        &mut self,
+        agent: Option<reqwest::header::HeaderValue>,
+    ) -> Result<(Vec<Info>, Vec<(String, Error)>)> {
+        match self {
+            Inner::Http { endp, .. } => endp.to_chains(agent).await,
+        }
+    }
+
+    fn plan(&self, state: State, latest: &Random) -> Result<BootPlan> {
+        match self {
+            Inner::Http { endp, .. } => endp.plan(state, latest),
+        }
+    }
+
     async fn boot_phase2(
         &mut self,
         state: State,
@@ -289,9 +1155,141 @@ impl Inner {
         }
     }
 
+    async fn reverify(
+        &mut self,
+        state: &State,
+        from: u128,
+        till: u128,
+        agent: Option<reqwest::header::HeaderValue>,
+    ) -> Result<ReverifyReport> {
+        match self {
+            Inner::Http { endp, .. } => endp.reverify(state, from, till, agent).await,
+        }
+    }
+
+    fn to_cache_headers(&self) -> Option<(time::Instant, CacheHeaders)> {
+        match self {
+            Inner::Http { endp, .. } => endp.to_cache_headers(),
+        }
+    }
+
+    fn to_detached(&self) -> Inner {
+        match self {
+            Inner::Http { name, endp } => Inner::Http {
+                name: name.clone(),
+                endp: endp.to_detached(),
+            },
+        }
+    }
+
+    fn fold_detached(&mut self, detached: Inner, validated: bool) {
+        match (self, detached) {
+            (Inner::Http { endp, .. }, Inner::Http { endp: detached, .. }) => {
+                endp.fold_detached(detached, validated)
+            }
+        }
+    }
+
+    fn to_endpoint_stats(&self) -> EndpointStats {
+        match self {
+            Inner::Http { endp, .. } => endp.to_endpoint_stats(),
+        }
+    }
+
+    fn to_samples(&self) -> Vec<time::Duration> {
+        match self {
+            Inner::Http { endp, .. } => endp.to_samples(),
+        }
+    }
+
     fn to_elapsed(&self) -> time::Duration {
         match self {
             Inner::Http { endp, .. } => endp.to_elapsed(),
         }
     }
+
+    fn to_category(&self) -> Category {
+        match self {
+            Inner::Http { endp, .. } => endp.to_category(),
+        }
+    }
+
+    fn to_url(&self) -> String {
+        match self {
+            Inner::Http { endp, .. } => endp.to_base_url(),
+        }
+    }
+
+    fn to_percentile(&self, p: u8) -> Option<time::Duration> {
+        match self {
+            Inner::Http { endp, .. } => endp.to_percentile(p),
+        }
+    }
+}
+
+fn is_round_error(err: &Error) -> bool {
+    match err {
+        Error::MissingRounds(_, _) | Error::RoundNotYetAvailable(_, _, _) => true,
+        _ => false,
+    }
+}
+
+// load persisted state into `config`. State that fails to load, or that
+// belongs to a different chain, is ignored and client boots afresh.
+fn load_state(dir: &StateDir, mut config: Config) -> (Config, Samples) {
+    let info = match dir.load_info() {
+        Ok(Some(info)) => info,
+        _ => return (config, Samples::default()),
+    };
+    match config.info.as_ref() {
+        Some(cfg_info) if cfg_info.hash != info.hash => return (config, Samples::default()),
+        Some(_) => (),
+        None => config.info = Some(info),
+    }
+    if let Ok(Some(cp)) = dir.load_check_point() {
+        match config.check_point.as_ref() {
+            Some(check_point) if check_point.round >= cp.round => (),
+            _ => config.check_point = Some(cp),
+        }
+    }
+    (config, dir.load_stats().unwrap_or_default())
 }
+
+// Send request to `e1`, and if it does not respond within `after`, send
+// the same request to `e2`. Return the response from whichever responds
+// first, if the first response is an error wait for the other response.
+async fn hedged_get(
+    e1: &mut Inner,
+    e2: &mut Inner,
+    state: State,
+    round: Option<u128>,
+    agent: Option<reqwest::header::HeaderValue>,
+    after: time::Duration,
+) -> (Option<Result<(State, Random)>>, Option<Result<(State, Random)>>) {
+    use futures::future::{select, Either};
+
+    let f1 = e1.get(state.clone(), round, agent.clone());
+    futures::pin_mut!(f1);
+    let f1 = match select(f1, futures_timer::Delay::new(after)).await {
+        Either::Left((Ok(val), _)) => return (Some(Ok(val)), None),
+        // failed before the delay, send the request to `e2` right away.
+        Either::Left((res1, _)) => {
+            let res2 = e2.get(state, round, agent).await;
+            return (Some(res1), Some(res2));
+        }
+        Either::Right((_, f1)) => f1,
+    };
+
+    let f2 = e2.get(state, round, agent);
+    futures::pin_mut!(f2);
+    match select(f1, f2).await {
+        Either::Left((Ok(val), _)) => (Some(Ok(val)), None),
+        Either::Left((res1, f2)) => (Some(res1), Some(f2.await)),
+        Either::Right((Ok(val), _)) => (None, Some(Ok(val))),
+        Either::Right((res2, f1)) => (Some(f1.await), Some(res2)),
+    }
+}
+
+#[cfg(test)]
+#[path = "endpoints_test.rs"]
+mod endpoints_test;
@@ -0,0 +1,465 @@
+use crate::fixtures::mainnet;
+
+use super::*;
+
+fn new_endpoints(info: Info, agreement: Agreement) -> (Endpoints, Inner, Inner) {
+    let mut config = Config::default();
+    config.set_info(Some(info)).set_agreement(agreement);
+    let endpoints = Endpoints::from_config("test", config);
+
+    let name = "test".to_string();
+    let e1 = Inner::Http {
+        name: name.clone(),
+        endp: Http::new_drand_api(),
+    };
+    let e2 = Inner::Http {
+        name,
+        endp: Http::new_cloudflare(),
+    };
+    (endpoints, e1, e2)
+}
+
+#[test]
+fn test_reconcile() {
+    let (info, r1) = mainnet();
+    let mut r2 = r1.clone();
+    r2.round = 2;
+    r2.signature.0[10] ^= 0xFF;
+    let s = State::default();
+
+    // prefer higher
+    let (endps, e1, e2) = new_endpoints(info.clone(), Agreement::PreferHigher);
+    let val1 = (s.clone(), r1.clone());
+    let val2 = (s.clone(), r2.clone());
+    let (_, r) = endps.reconcile(&e1, val1.clone(), &e2, val2.clone()).unwrap();
+    assert_eq!(r, r2);
+    let (_, r) = endps.reconcile(&e2, val2.clone(), &e1, val1.clone()).unwrap();
+    assert_eq!(r, r2);
+    let (_, r) = endps.reconcile(&e1, val1.clone(), &e2, val1.clone()).unwrap();
+    assert_eq!(r, r1);
+
+    // same round, different bytes
+    let mut bad = r1.clone();
+    bad.randomness.0[0] ^= 0xFF;
+    let valb = (s.clone(), bad);
+    match endps.reconcile(&e1, val1.clone(), &e2, valb.clone()) {
+        Err(Error::Equivocation(_, _)) => (),
+        res => panic!("unexpected {:?}", res.map(|(_, r)| r)),
+    }
+
+    // require match
+    let (endps, e1, e2) = new_endpoints(info.clone(), Agreement::RequireMatch);
+    let (_, r) = endps.reconcile(&e1, val1.clone(), &e2, val1.clone()).unwrap();
+    assert_eq!(r, r1);
+    match endps.reconcile(&e1, val1.clone(), &e2, val2.clone()) {
+        Err(Error::Invalid(_, _)) => (),
+        res => panic!("unexpected {:?}", res.map(|(_, r)| r)),
+    }
+
+    // prefer verified, verified round-1 over tampered round-2
+    let (endps, e1, e2) = new_endpoints(info, Agreement::PreferVerified);
+    let (_, r) = endps.reconcile(&e1, val1.clone(), &e2, val2.clone()).unwrap();
+    assert_eq!(r, r1);
+    let (_, r) = endps.reconcile(&e2, val2.clone(), &e1, val1.clone()).unwrap();
+    assert_eq!(r, r1);
+    match endps.reconcile(&e1, val2.clone(), &e2, val2.clone()) {
+        Err(Error::NotSecure(_, _)) => (),
+        res => panic!("unexpected {:?}", res.map(|(_, r)| r)),
+    }
+}
+
+#[derive(Debug)]
+struct FixedClock(time::SystemTime);
+
+impl Clock for FixedClock {
+    fn now(&self) -> time::SystemTime {
+        self.0
+    }
+}
+
+#[test]
+fn test_is_historical() {
+    let (info, _) = mainnet();
+
+    // 10 rounds after genesis.
+    let now = info.genesis_time + time::Duration::from_secs(300);
+    let mut config = Config::default();
+    config
+        .set_info(Some(info.clone()))
+        .set_clock(Arc::new(FixedClock(now)));
+    let endps = Endpoints::from_config("test", config);
+
+    assert!(endps.is_historical(1));
+    assert!(endps.is_historical(10));
+    assert!(!endps.is_historical(11));
+    assert!(!endps.is_historical(12));
+
+    // clock before genesis, no round is historical.
+    let now = info.genesis_time - time::Duration::from_secs(1);
+    let mut config = Config::default();
+    config
+        .set_info(Some(info))
+        .set_clock(Arc::new(FixedClock(now)));
+    let endps = Endpoints::from_config("test", config);
+    assert!(!endps.is_historical(1));
+}
+
+#[test]
+fn test_root_of_trust() {
+    use futures::executor::block_on;
+
+    let (info, _) = mainnet();
+    let hash = info.hash.as_bytes().to_vec();
+
+    let mut endps = Endpoints::from_config("test", Config::default());
+    assert_eq!(block_on(endps.to_root_of_trust(None)).unwrap(), None);
+    let res = block_on(endps.to_root_of_trust(Some(vec![1; 32])));
+    assert_eq!(res.unwrap(), Some(vec![1; 32]));
+
+    // supplied info qualifies, no network call.
+    let mut config = Config::default();
+    config
+        .set_info(Some(info.clone()))
+        .set_max_period(Some(time::Duration::from_secs(30)));
+    let mut endps = Endpoints::from_config("test", config);
+    assert_eq!(block_on(endps.to_root_of_trust(None)).unwrap(), Some(hash));
+
+    // configured chain-hash is preferred over max_period.
+    let mut config = Config::default();
+    config
+        .set_chain_hash(Some(vec![2; 32]))
+        .set_max_period(Some(time::Duration::from_secs(3)));
+    let mut endps = Endpoints::from_config("test", config);
+    assert_eq!(block_on(endps.to_root_of_trust(None)).unwrap(), Some(vec![2; 32]));
+
+    // supplied info does not qualify, chains are listed from endpoints.
+    let mut config = Config::default();
+    config
+        .set_info(Some(info))
+        .set_max_period(Some(time::Duration::from_secs(3)));
+    let mut endps = Endpoints::from_config("test", config);
+    assert!(block_on(endps.to_root_of_trust(None)).is_err());
+}
+
+#[test]
+fn test_breaker() {
+    use std::sync::Mutex;
+
+    let (info, _) = mainnet();
+    let breaker = Breaker {
+        failures: 2,
+        ..Breaker::default()
+    };
+    let mut config = Config::default();
+    config
+        .set_info(Some(info))
+        .set_breaker(breaker)
+        .set_clock(Arc::new(FixedClock(time::UNIX_EPOCH)));
+    let mut endps = Endpoints::from_config("test", config);
+    let (_, e1, e2) = new_endpoints(Info::default(), Agreement::default());
+    endps.endpoints = vec![e1.clone(), e2.clone()];
+
+    let quarantined = Arc::new(Mutex::new(vec![]));
+    {
+        let quarantined = Arc::clone(&quarantined);
+        endps.add_listener(Box::new(move |event| {
+            if let Event::Quarantine(url) = event {
+                quarantined.lock().unwrap().push(url.clone())
+            }
+        }));
+    }
+
+    let fail: Result<()> = err_at!(IOError, msg: format!("connection refused"));
+    let missing: Result<()> = Err(Error::MissingRounds("".to_string(), 10..=10));
+
+    // round errors are not the endpoint's fault.
+    for _ in 0..4 {
+        endps.put_endpoint(&e1, &missing, Some(10));
+    }
+    endps.put_endpoint(&e1, &fail, None);
+    assert_eq!(endps.to_circuit_state(&e1.to_url()), CircuitState::Closed);
+    endps.put_endpoint(&e1, &fail, None);
+    assert_eq!(endps.to_circuit_state(&e1.to_url()), CircuitState::Open);
+    endps.put_endpoint(&e1, &fail, None);
+
+    let stats = endps.to_endpoint_stats();
+    assert!(stats[0].quarantined);
+    assert_eq!(stats[1].circuit, CircuitState::Closed);
+    assert_eq!(*quarantined.lock().unwrap(), vec![e1.to_url()]);
+
+    match endps.get_endpoint_pair(Category::Origin) {
+        (Some(e), None) => assert_eq!(e.to_url(), e2.to_url()),
+        _ => panic!("expected single endpoint"),
+    }
+}
+
+#[test]
+fn test_rate_limited() {
+    use std::sync::Mutex;
+
+    let (info, _) = mainnet();
+    let mut config = Config::default();
+    config
+        .set_info(Some(info))
+        .set_clock(Arc::new(FixedClock(time::UNIX_EPOCH)));
+    let mut endps = Endpoints::from_config("test", config);
+    let (_, e1, e2) = new_endpoints(Info::default(), Agreement::default());
+    endps.endpoints = vec![e1.clone(), e2.clone()];
+
+    let pauses = Arc::new(Mutex::new(vec![]));
+    {
+        let pauses = Arc::clone(&pauses);
+        endps.add_listener(Box::new(move |event| {
+            if let Event::RateLimited { url, pause } = event {
+                pauses.lock().unwrap().push((url.clone(), *pause))
+            }
+        }));
+    }
+
+    // rate limiting is not counted as failure, endpoint is paused instead.
+    let after = Some(time::Duration::from_secs(10));
+    let limited: Result<()> = Err(Error::RateLimited("".to_string(), after));
+    for _ in 0..10 {
+        endps.put_endpoint(&e1, &limited, None);
+    }
+    assert_eq!(endps.to_circuit_state(&e1.to_url()), CircuitState::Closed);
+    assert!(endps.is_paused(&e1.to_url()));
+    assert!(!endps.is_paused(&e2.to_url()));
+    assert_eq!(pauses.lock().unwrap().len(), 10);
+    assert_eq!(pauses.lock().unwrap()[0], (e1.to_url(), time::Duration::from_secs(10)));
+
+    match endps.get_endpoint_pair(Category::Origin) {
+        (Some(e), None) => assert_eq!(e.to_url(), e2.to_url()),
+        _ => panic!("expected single endpoint"),
+    }
+
+    // without Retry-After, paused for breaker's cool-off.
+    let limited: Result<()> = Err(Error::RateLimited("".to_string(), None));
+    endps.put_endpoint(&e2, &limited, None);
+    assert_eq!(pauses.lock().unwrap()[10], (e2.to_url(), Breaker::default().cool_off));
+    assert!(matches!(endps.get_endpoint_pair(Category::Origin), (None, None)));
+}
+
+#[test]
+fn test_boot_validation() {
+    use crate::fixtures::{info_reply, mainnet_round1, round_reply, MockRelay, Reply};
+
+    // good relays are slower than the one failing validation.
+    let good = || {
+        MockRelay::start(|path| {
+            std::thread::sleep(time::Duration::from_millis(20));
+            match path {
+                "/info" => info_reply(),
+                _ => round_reply(&mainnet_round1()),
+            }
+        })
+    };
+    let (e1, e3) = (good(), good());
+    let e2 = MockRelay::start(|path| match path {
+        "/info" => info_reply(),
+        "/public/latest" => round_reply(&mainnet_round1()),
+        _ => Reply::new(500, ""),
+    });
+
+    let (info, _) = mainnet();
+    let mut config = Config::default();
+    config
+        .set_info(Some(info.clone()))
+        .set_security_policy(SecurityPolicy::Assumed);
+    let mut endps = Endpoints::from_config("test", config);
+    for relay in [&e1, &e2, &e3].iter() {
+        let endp = Endpoint::Http {
+            url: relay.to_url(),
+            category: Category::Origin,
+        };
+        endps.add_endpoint(endp, None);
+    }
+
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(endps.boot(Some(info.hash.as_bytes().to_vec()))).unwrap();
+    assert!(e2.to_hits().len() > 2, "{:?}", e2.to_hits());
+
+    // endpoint failing validation is left out of endpoint selection.
+    match endps.get_endpoint_pair(Category::Origin) {
+        (Some(x), Some(y)) => {
+            let mut urls = vec![x.to_url(), y.to_url()];
+            urls.sort();
+            let mut expected = vec![e1.to_url(), e3.to_url()];
+            expected.sort();
+            assert_eq!(urls, expected);
+        }
+        _ => panic!("expected endpoint pair"),
+    }
+}
+
+#[test]
+fn test_root_of_trust_skipped() {
+    use std::sync::Mutex;
+
+    use crate::fixtures::{info_reply, mainnet_info_json, MockRelay, Reply};
+
+    // quicknet-like chain with keys on G2, that does not parse as Info.
+    let mut g2 = mainnet_info_json();
+    g2["public_key"] = serde_json::json!(hex::encode([0x83; 96]));
+    g2["hash"] = serde_json::json!(crate::QUICKNET_CHAIN_HASH);
+    g2["period"] = serde_json::json!(3);
+    let relay = MockRelay::start(move |path| {
+        let chains = [crate::MAINNET_CHAIN_HASH, crate::QUICKNET_CHAIN_HASH];
+        match path.trim_end_matches("/info").trim_start_matches('/') {
+            "chains" => Reply::new(200, &serde_json::json!(chains).to_string()),
+            hash if hash == crate::MAINNET_CHAIN_HASH => info_reply(),
+            hash if hash == crate::QUICKNET_CHAIN_HASH => Reply::new(200, &g2.to_string()),
+            _ => Reply::new(404, ""),
+        }
+    });
+
+    let mut config = Config::default();
+    config.set_max_period(Some(time::Duration::from_secs(30)));
+    let mut endps = Endpoints::from_config("test", config);
+    let endp = Endpoint::Http {
+        url: relay.to_url(),
+        category: Category::Origin,
+    };
+    endps.add_endpoint(endp, None);
+    let skipped = Arc::new(Mutex::new(vec![]));
+    {
+        let skipped = Arc::clone(&skipped);
+        endps.add_listener(Box::new(move |event| {
+            if let Event::ChainSkipped { hash, .. } = event {
+                skipped.lock().unwrap().push(hash.clone())
+            }
+        }));
+    }
+
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    let hash = rt.block_on(endps.to_root_of_trust(None)).unwrap();
+    assert_eq!(hash.map(hex::encode).as_deref(), Some(crate::MAINNET_CHAIN_HASH));
+    assert_eq!(*skipped.lock().unwrap(), vec![crate::QUICKNET_CHAIN_HASH.to_string()]);
+}
+
+#[test]
+fn test_persist() {
+    use std::{fs, sync::Mutex};
+
+    let dir = std::env::temp_dir().join("drand-rs-test-persist");
+    fs::remove_dir_all(&dir).ok();
+    fs::remove_file(&dir).ok();
+
+    let (info, r1) = mainnet();
+    let mut config = Config::default();
+    config
+        .set_info(Some(info))
+        .set_check_point(Some(r1))
+        .set_state_dir(Some(dir.clone()));
+    let mut endps = Endpoints::from_config("test", config);
+
+    // forced, else throttled till the interval is over.
+    let file = dir.join("check_point.json");
+    endps.persist(true);
+    assert!(file.exists());
+    fs::remove_file(&file).unwrap();
+    endps.persist(false);
+    assert!(!file.exists());
+    endps.persisted = endps.persisted.map(|at| at - PERSIST_INTERVAL);
+    endps.persist(false);
+    assert!(file.exists());
+    fs::remove_dir_all(&dir).unwrap();
+
+    // failures are emitted, not returned.
+    fs::write(&dir, b"not a directory").unwrap();
+    let failures = Arc::new(Mutex::new(vec![]));
+    {
+        let failures = Arc::clone(&failures);
+        endps.add_listener(Box::new(move |event| {
+            if let Event::PersistFailure { msg } = event {
+                failures.lock().unwrap().push(msg.clone())
+            }
+        }));
+    }
+    endps.persist(true);
+    assert_eq!(failures.lock().unwrap().len(), 1);
+    fs::remove_file(&dir).unwrap();
+}
+
+#[test]
+fn test_hedged_get_early_error() {
+    use crate::fixtures::{mainnet_info, mainnet_round1, round_reply, MockRelay, Reply};
+
+    let bad = MockRelay::start(|_| Reply::new(500, ""));
+    let good = MockRelay::start(|_| round_reply(&mainnet_round1()));
+    let new_inner = |relay: &MockRelay| Inner::Http {
+        name: "test".to_string(),
+        endp: Http::new(&relay.to_url(), Category::Origin),
+    };
+    let (mut e1, mut e2) = (new_inner(&bad), new_inner(&good));
+    let state = State {
+        info: mainnet_info(),
+        ..State::default()
+    };
+
+    // e1 fails before the hedge delay, e2 is asked without waiting for it.
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    let after = time::Duration::from_secs(10);
+    let start = time::Instant::now();
+    let fut = hedged_get(&mut e1, &mut e2, state, Some(1), None, after);
+    match rt.block_on(fut) {
+        (Some(Err(_)), Some(Ok((_, r)))) => assert_eq!(r, mainnet_round1()),
+        (res1, res2) => {
+            let rounds = |res: Option<Result<(State, Random)>>| res.map(|res| res.map(|v| v.1));
+            panic!("unexpected {:?} {:?}", rounds(res1), rounds(res2))
+        }
+    }
+    assert!(start.elapsed() < after);
+    assert_eq!(good.to_hits().len(), 1);
+}
+
+#[test]
+fn test_hedged_get_not_yet_available() {
+    use futures::future::{select, Either};
+
+    use crate::{
+        fixtures::{info_reply, mainnet_round1, round_reply, MockRelay, Reply},
+        Hedge,
+    };
+
+    let new_relay = || {
+        MockRelay::start(|path| match path {
+            "/info" => info_reply(),
+            "/public/latest" => round_reply(&mainnet_round1()),
+            _ => Reply::new(425, ""),
+        })
+    };
+    let (r1, r2) = (new_relay(), new_relay());
+
+    let (info, check_point) = mainnet();
+    let round = info.round_at(time::SystemTime::now()).unwrap() + 100;
+    let mut config = Config::default();
+    config
+        .set_info(Some(info))
+        .set_check_point(Some(check_point))
+        .set_hedge(Some(Hedge::After(time::Duration::from_secs(1))));
+    let mut endps = Endpoints::from_config("test", config);
+    for relay in [&r1, &r2].iter() {
+        let endp = Endpoint::Http {
+            url: relay.to_url(),
+            category: Category::Origin,
+        };
+        endps.add_endpoint(endp, None);
+    }
+
+    // round errors are returned, not retried forever.
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    let res = rt.block_on(async {
+        let fut = endps.get(Some(round));
+        futures::pin_mut!(fut);
+        match select(fut, futures_timer::Delay::new(time::Duration::from_secs(10))).await {
+            Either::Left((res, _)) => Some(res),
+            Either::Right(_) => None,
+        }
+    });
+    match res {
+        Some(Err(Error::RoundNotYetAvailable(_, r, _))) => assert_eq!(r, round),
+        Some(res) => panic!("unexpected {:?}", res),
+        None => panic!("get did not return"),
+    }
+}
@@ -0,0 +1,64 @@
+//! Module implement events emitted by a client.
+
+use std::time;
+
+use crate::Random;
+
+/// Events emitted by a client to its listeners, refer
+/// [Client::add_listener](crate::Client::add_listener).
+#[derive(Clone, Debug)]
+pub enum Event {
+    /// A new round of randomness, later than any previously returned
+    /// round, is returned by the client.
+    NewRound(Random),
+    /// Endpoint `from` failed to serve a request, which was served by
+    /// endpoint `to`.
+    Failover { from: String, to: String },
+    /// Endpoint is left out of endpoint selection, after repeated failures,
+    /// its circuit is open. Refer [Config::breaker].
+    ///
+    /// [Config::breaker]: crate::Config::breaker
+    Quarantine(String),
+    /// Randomness failed verification.
+    VerificationFailure { round: Option<u128>, msg: String },
+    /// Verified check_point advanced to a newer round.
+    CheckPoint(Random),
+    /// Watch gave up on a round, that could not be fetched within the
+    /// late tolerance, refer [Config::late_tolerance].
+    ///
+    /// [Config::late_tolerance]: crate::Config::late_tolerance
+    MissedRound { round: u128, msg: String },
+    /// No new round is observed after `round`, for `elapsed`, refer
+    /// [Config::stall_periods]. Emitted once for each stall.
+    ///
+    /// [Config::stall_periods]: crate::Config::stall_periods
+    ChainStalled { round: u128, elapsed: time::Duration },
+    /// Endpoint responded with 429 Too Many Requests, and is left out of
+    /// endpoint selection for `pause`, as told by its Retry-After header,
+    /// or else for [Breaker::cool_off].
+    ///
+    /// [Breaker::cool_off]: crate::Breaker::cool_off
+    RateLimited { url: String, pause: time::Duration },
+    /// Client sent `requests` in the current hour, exhausting its
+    /// `budget`, refer [Config::request_budget]. Emitted once for the
+    /// hour.
+    ///
+    /// [Config::request_budget]: crate::Config::request_budget
+    BudgetExceeded { requests: u64, budget: u64 },
+    /// Chain listed by an endpoint is skipped while picking a chain by its
+    /// period, as its info could not be parsed, like for chains with keys
+    /// on G2. Refer [Config::max_period].
+    ///
+    /// [Config::max_period]: crate::Config::max_period
+    ChainSkipped { hash: String, msg: String },
+    /// Client state could not be persisted, refer [Config::state_dir].
+    /// Client carries on, and persists again on the next update.
+    ///
+    /// [Config::state_dir]: crate::Config::state_dir
+    PersistFailure { msg: String },
+}
+
+/// Listener callback for client events. Called while the client is
+/// locked, and must not call back into the client, refer
+/// [Client::add_listener](crate::Client::add_listener).
+pub type Listener = Box<dyn Fn(&Event) + Send>;
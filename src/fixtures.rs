@@ -0,0 +1,189 @@
+//! Module implement fixtures shared by unit tests.
+
+use std::{
+    convert::TryFrom,
+    io::{Read, Write},
+    net,
+    sync::{Arc, Mutex},
+    thread, time,
+};
+
+use crate::{http::RandomJson, ChainHash, Info, PublicKey, Random, Randomness, Signature};
+
+pub(crate) const MAINNET_PUBLIC_KEY: &str = "868f005eb8e6e4ca0a47c8a77ceaa5309a47978a7c71bc5cce96366b5d7a569937c529eeda66c7293784a9402801af31";
+pub(crate) const MAINNET_GROUP_HASH: &str =
+    "176f93498eac9ca337150b46d21dd58673ea4e3581185f869672e59fa4cb390a";
+pub(crate) const MAINNET_SIGNATURE_1: &str = "8d61d9100567de44682506aea1a7a6fa6e5491cd27a0a0ed349ef6910ac5ac20ff7bc3e09d7c046566c9f7f3c6f3b10104990e7cb424998203d8f7de586fb7fa5f60045417a432684f85093b06ca91c769f0e7ca19268375e659c2a2352b4655";
+pub(crate) const MAINNET_RANDOMNESS_1: &str =
+    "101297f1ca7dc44ef6088d94ad5fb7ba03455dc33d53ddb412bbc4564ed986ec";
+
+/// Mainnet chain info.
+pub(crate) fn mainnet_info() -> Info {
+    Info {
+        public_key: PublicKey::try_from(MAINNET_PUBLIC_KEY).unwrap(),
+        period: time::Duration::from_secs(30),
+        genesis_time: time::UNIX_EPOCH + time::Duration::from_secs(1595431050),
+        hash: ChainHash::try_from(crate::MAINNET_CHAIN_HASH).unwrap(),
+        group_hash: hex::decode(MAINNET_GROUP_HASH).unwrap(),
+    }
+}
+
+/// Mainnet chain info, as served by v1 api.
+pub(crate) fn mainnet_info_json() -> serde_json::Value {
+    serde_json::json!({
+        "public_key": MAINNET_PUBLIC_KEY,
+        "period": 30,
+        "genesis_time": 1595431050,
+        "hash": crate::MAINNET_CHAIN_HASH,
+        "groupHash": MAINNET_GROUP_HASH,
+    })
+}
+
+/// Mainnet chain info, as served by v2 api.
+pub(crate) fn mainnet_info_json_v2() -> serde_json::Value {
+    serde_json::json!({
+        "public_key": MAINNET_PUBLIC_KEY,
+        "period": 30,
+        "genesis_time": 1595431050,
+        "chain_hash": crate::MAINNET_CHAIN_HASH,
+        "genesis_seed": MAINNET_GROUP_HASH,
+    })
+}
+
+/// Mainnet round 1.
+pub(crate) fn mainnet_round1() -> Random {
+    Random {
+        round: 1,
+        randomness: Randomness::try_from(MAINNET_RANDOMNESS_1).unwrap(),
+        signature: Signature::try_from(MAINNET_SIGNATURE_1).unwrap(),
+        previous_signature: hex::decode(MAINNET_GROUP_HASH).unwrap(),
+    }
+}
+
+/// Mainnet chain info and round 1.
+pub(crate) fn mainnet() -> (Info, Random) {
+    (mainnet_info(), mainnet_round1())
+}
+
+/// Round filled with byte `b`, not verifiable.
+pub(crate) fn new_random(round: u128, b: u8) -> Random {
+    Random {
+        round,
+        randomness: Randomness([b; 32]),
+        signature: Signature([b; 96]),
+        previous_signature: vec![],
+    }
+}
+
+/// Round whose randomness is the hash of its signature, passes
+/// [Random::validate], not verifiable.
+pub(crate) fn new_validated(round: u128) -> Random {
+    let mut r = new_random(round, 0);
+    r.signature.0[..16].copy_from_slice(&round.to_be_bytes());
+    r.randomness = crate::recompute_randomness(&r);
+    r
+}
+
+/// Response by [MockRelay].
+pub(crate) struct Reply {
+    pub(crate) status: u16,
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) body: String,
+}
+
+impl Reply {
+    pub(crate) fn new(status: u16, body: &str) -> Reply {
+        Reply {
+            status,
+            headers: vec![],
+            body: body.to_string(),
+        }
+    }
+
+    pub(crate) fn set_header(mut self, name: &str, value: &str) -> Reply {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+/// Mainnet info, as served by relays.
+pub(crate) fn info_reply() -> Reply {
+    Reply::new(200, &mainnet_info_json().to_string())
+}
+
+/// Round, as served by relays.
+pub(crate) fn round_reply(r: &Random) -> Reply {
+    Reply::new(200, &serde_json::to_string(&RandomJson::from(r)).unwrap())
+}
+
+/// Relay on a local port, serving responses from a handler, called with
+/// the request path. Requests are recorded along with their arrival time.
+pub(crate) struct MockRelay {
+    url: String,
+    hits: Arc<Mutex<Vec<(time::Instant, String)>>>,
+}
+
+impl MockRelay {
+    pub(crate) fn start<F>(handler: F) -> MockRelay
+    where
+        F: 'static + Fn(&str) -> Reply + Send,
+    {
+        let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let hits = Arc::new(Mutex::new(vec![]));
+        {
+            let hits = Arc::clone(&hits);
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let mut stream = match stream {
+                        Ok(stream) => stream,
+                        Err(_) => break,
+                    };
+                    let path = match read_path(&mut stream) {
+                        Some(path) => path,
+                        None => continue,
+                    };
+                    hits.lock().unwrap().push((time::Instant::now(), path.clone()));
+                    write_reply(&mut stream, handler(&path)).ok();
+                }
+            });
+        }
+        MockRelay { url, hits }
+    }
+
+    pub(crate) fn to_url(&self) -> String {
+        self.url.clone()
+    }
+
+    /// Return requested paths, along with their arrival time.
+    pub(crate) fn to_hits(&self) -> Vec<(time::Instant, String)> {
+        self.hits.lock().unwrap().clone()
+    }
+}
+
+// read request head, return the request path.
+fn read_path(stream: &mut net::TcpStream) -> Option<String> {
+    let mut head = vec![];
+    let mut buf = [0_u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        match stream.read(&mut buf) {
+            Ok(0) | Err(_) => return None,
+            Ok(n) => head.extend_from_slice(&buf[..n]),
+        }
+    }
+    let head = String::from_utf8_lossy(&head);
+    head.split_whitespace().nth(1).map(|path| path.to_string())
+}
+
+fn write_reply(stream: &mut net::TcpStream, reply: Reply) -> std::io::Result<()> {
+    let mut resp = format!("HTTP/1.1 {} Mock\r\n", reply.status);
+    resp.push_str("Content-Type: application/json\r\n");
+    resp.push_str(&format!("Content-Length: {}\r\n", reply.body.len()));
+    resp.push_str("Connection: close\r\n");
+    for (name, value) in reply.headers.iter() {
+        resp.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    resp.push_str("\r\n");
+    resp.push_str(&reply.body);
+    stream.write_all(resp.as_bytes())
+}
@@ -0,0 +1,165 @@
+//! Module implement allow-list and deny-list of IP addresses, in CIDR
+//! notation, that a client may connect to.
+//!
+//! The filter is applied twice, host names are resolved and checked
+//! before every request is sent, and the remote address of every response
+//! is checked before its body is consumed. The second check makes DNS
+//! rebinding visible, that is, a host name resolving to an allowed
+//! address when checked but to a forbidden address when connected. Note
+//! that such a request is already sent, by the time its remote address is
+//! known, the response is rejected. Responses with unknown remote address
+//! are rejected as well.
+
+use std::{
+    fmt,
+    net::{IpAddr, Ipv4Addr},
+    result,
+    str::FromStr,
+};
+
+use crate::{Error, Result};
+
+/// Block of IP addresses in CIDR notation, like `10.0.0.0/8` or
+/// `2001:db8::/32`. A bare address is treated as a block of one.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct Cidr {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl FromStr for Cidr {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Cidr> {
+        let (addr, prefix) = match s.find('/') {
+            Some(n) => (&s[..n], Some(&s[n + 1..])),
+            None => (s, None),
+        };
+        let addr: IpAddr = err_at!(StringParse, addr.trim().parse(), format!("cidr {:?}", s))?;
+        let max = match addr {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        let prefix = match prefix {
+            Some(p) => err_at!(StringParse, p.trim().parse::<u8>(), format!("cidr {:?}", s))?,
+            None => max,
+        };
+        if prefix > max {
+            err_at!(Invalid, msg: format!("cidr {:?} prefix > {}", s, max))?
+        }
+        Ok(Cidr { addr, prefix })
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+impl Cidr {
+    /// Return whether `ip` falls within this block. IPv4-mapped IPv6
+    /// addresses are matched as IPv4.
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.addr, to_canonical(ip)) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = (!0_u32).checked_shl(32 - (self.prefix as u32)).unwrap_or(0);
+                (u32::from(net) & mask) == (u32::from(ip) & mask)
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = (!0_u128).checked_shl(128 - (self.prefix as u32)).unwrap_or(0);
+                (u128::from(net) & mask) == (u128::from(ip) & mask)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Allow-list and deny-list of IP addresses, refer [Config::ip_filter].
+///
+/// An address is allowed if it does not match any of the denied blocks,
+/// and, if the allow-list is not empty, it matches one of the allowed
+/// blocks.
+///
+/// [Config::ip_filter]: crate::Config::ip_filter
+#[derive(Clone, Default, Eq, PartialEq, Debug)]
+pub struct IpFilter {
+    allow: Vec<Cidr>,
+    deny: Vec<Cidr>,
+}
+
+impl IpFilter {
+    /// Add `cidr` block to the allow-list.
+    pub fn allow(&mut self, cidr: &str) -> Result<&mut Self> {
+        self.allow.push(cidr.parse()?);
+        Ok(self)
+    }
+
+    /// Add `cidr` block to the deny-list.
+    pub fn deny(&mut self, cidr: &str) -> Result<&mut Self> {
+        self.deny.push(cidr.parse()?);
+        Ok(self)
+    }
+
+    /// Return whether the client may connect to `ip`.
+    pub fn is_allowed(&self, ip: &IpAddr) -> bool {
+        if self.deny.iter().any(|c| c.contains(ip)) {
+            false
+        } else if self.allow.is_empty() {
+            true
+        } else {
+            self.allow.iter().any(|c| c.contains(ip))
+        }
+    }
+
+    /// Resolve the host in `url`, and return NotSecure error if any of the
+    /// resolved addresses is not allowed.
+    pub(crate) async fn check_url(&self, url: &str) -> Result<()> {
+        let u = err_at!(StringParse, reqwest::Url::parse(url), format!("url {:?}", url))?;
+        let host = match u.host_str() {
+            Some(host) => host.trim_start_matches('[').trim_end_matches(']'),
+            None => err_at!(Invalid, msg: format!("url {:?} without host", url))?,
+        };
+        let port = u.port_or_known_default().unwrap_or(0);
+        // literal addresses are not looked up.
+        let res = tokio::net::lookup_host((host, port)).await;
+        let addrs = err_at!(IOError, res, format!("resolve {:?}", url))?;
+        for addr in addrs {
+            self.check_ip(url, addr.ip())?;
+        }
+        Ok(())
+    }
+
+    /// Return NotSecure error if `resp` is from an address that is not
+    /// allowed, or if its remote address is not known.
+    pub(crate) fn check_response(&self, url: &str, resp: &reqwest::Response) -> Result<()> {
+        match resp.remote_addr() {
+            Some(addr) => self.check_ip(url, addr.ip()),
+            None => err_at!(NotSecure, msg: format!("{} unknown remote address", url)),
+        }
+    }
+
+    fn check_ip(&self, url: &str, ip: IpAddr) -> Result<()> {
+        match self.is_allowed(&ip) {
+            true => Ok(()),
+            false => err_at!(NotSecure, msg: format!("{} resolved to forbidden {}", url, ip)),
+        }
+    }
+}
+
+fn to_canonical(ip: &IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(ip6) => match ip6.segments() {
+            [0, 0, 0, 0, 0, 0xffff, hi, lo] => {
+                let (hi, lo) = (hi.to_be_bytes(), lo.to_be_bytes());
+                IpAddr::V4(Ipv4Addr::new(hi[0], hi[1], lo[0], lo[1]))
+            }
+            _ => *ip,
+        },
+        _ => *ip,
+    }
+}
+
+#[cfg(test)]
+#[path = "ipfilter_test.rs"]
+mod ipfilter_test;
@@ -0,0 +1,60 @@
+use super::*;
+
+fn ip(s: &str) -> IpAddr {
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_cidr() {
+    let cidr: Cidr = "10.0.0.0/8".parse().unwrap();
+    assert!(cidr.contains(&ip("10.1.2.3")));
+    assert!(!cidr.contains(&ip("11.0.0.1")));
+    assert!(cidr.contains(&ip("::ffff:10.9.8.7")));
+    assert!(!cidr.contains(&ip("::1")));
+    assert_eq!(cidr.to_string(), "10.0.0.0/8");
+
+    let cidr: Cidr = "0.0.0.0/0".parse().unwrap();
+    assert!(cidr.contains(&ip("203.0.113.9")));
+
+    let cidr: Cidr = "192.168.1.7".parse().unwrap();
+    assert!(cidr.contains(&ip("192.168.1.7")));
+    assert!(!cidr.contains(&ip("192.168.1.8")));
+
+    let cidr: Cidr = "2001:db8::/32".parse().unwrap();
+    assert!(cidr.contains(&ip("2001:db8:ffff::1")));
+    assert!(!cidr.contains(&ip("2001:db9::1")));
+
+    let cidr: Cidr = "::/0".parse().unwrap();
+    assert!(cidr.contains(&ip("fe80::1")));
+
+    assert!("10.0.0.0/33".parse::<Cidr>().is_err());
+    assert!("::/129".parse::<Cidr>().is_err());
+    assert!("10.0.0/8".parse::<Cidr>().is_err());
+    assert!("10.0.0.0/x".parse::<Cidr>().is_err());
+}
+
+#[test]
+fn test_ip_filter() {
+    let filter = IpFilter::default();
+    assert!(filter.is_allowed(&ip("127.0.0.1")));
+
+    let mut filter = IpFilter::default();
+    filter.deny("127.0.0.0/8").unwrap().deny("::1").unwrap();
+    assert!(!filter.is_allowed(&ip("127.0.0.1")));
+    assert!(!filter.is_allowed(&ip("::1")));
+    assert!(filter.is_allowed(&ip("104.16.0.1")));
+
+    filter.allow("104.16.0.0/13").unwrap();
+    assert!(filter.is_allowed(&ip("104.16.0.1")));
+    assert!(!filter.is_allowed(&ip("8.8.8.8")));
+
+    // deny takes precedence over allow.
+    filter.deny("104.16.0.1").unwrap();
+    assert!(!filter.is_allowed(&ip("104.16.0.1")));
+
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    assert!(rt.block_on(filter.check_url("http://127.0.0.1:8080")).is_err());
+    assert!(rt.block_on(filter.check_url("http://[::1]:8080")).is_err());
+    assert!(rt.block_on(filter.check_url("http://104.16.0.2")).is_ok());
+    assert!(rt.block_on(filter.check_url("not a url")).is_err());
+}
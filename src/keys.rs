@@ -0,0 +1,121 @@
+//! Module implement rotating symmetric keys derived from randomness.
+//!
+//! A common pattern for time-bucketed tokens, where a key is valid for the
+//! duration of a round. Key for a round is derived using HKDF-SHA256, with
+//! the round's randomness as salt, application's static secret as input
+//! key material and `drand-rs-key-rotation` followed by round number, as
+//! 16 byte big-endian integer, as info.
+//!
+//! ```ignore
+//! let mut ring = KeyRing::new(secret);
+//! for r in client.watch()? {
+//!     ring.rotate(&r?);
+//! }
+//! ```
+//!
+//! Rounds supplied to [KeyRing::rotate] must be verified, use a client
+//! configured as secure.
+
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+use std::fmt;
+
+use crate::Random;
+
+const KEY_INFO: &[u8] = b"drand-rs-key-rotation";
+
+/// Key derived for a round.
+#[derive(Clone, Eq, PartialEq)]
+pub struct RoundKey {
+    /// Round from which the key is derived.
+    pub round: u128,
+    /// Derived key.
+    pub key: [u8; 32],
+}
+
+// keys are not printed.
+impl fmt::Debug for RoundKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RoundKey<{}>", self.round)
+    }
+}
+
+/// Current and previous keys, rotated at round boundaries.
+///
+/// Previous key is retained, so that tokens issued just before the
+/// rotation can be accepted during a grace period.
+pub struct KeyRing {
+    secret: Vec<u8>,
+    current: Option<RoundKey>,
+    previous: Option<RoundKey>,
+}
+
+impl fmt::Debug for KeyRing {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "KeyRing<{:?},{:?}>", self.current, self.previous)
+    }
+}
+
+impl KeyRing {
+    /// Create a new key-ring with application's static `secret`.
+    pub fn new(secret: &[u8]) -> KeyRing {
+        KeyRing {
+            secret: secret.to_vec(),
+            current: None,
+            previous: None,
+        }
+    }
+
+    /// Rotate keys with a newer round `r`, return whether keys are
+    /// rotated. Rounds older than, or same as, the current key's round
+    /// are ignored.
+    pub fn rotate(&mut self, r: &Random) -> bool {
+        match self.current.as_ref() {
+            Some(current) if current.round >= r.round => false,
+            _ => {
+                let key = RoundKey {
+                    round: r.round,
+                    key: derive_key(&self.secret, r),
+                };
+                self.previous = self.current.replace(key);
+                true
+            }
+        }
+    }
+
+    /// Return the key for the latest round.
+    pub fn to_current(&self) -> Option<RoundKey> {
+        self.current.clone()
+    }
+
+    /// Return the key for the round before the latest round.
+    pub fn to_previous(&self) -> Option<RoundKey> {
+        self.previous.clone()
+    }
+
+    /// Return the key derived from `round`, if it is the current or the
+    /// previous key.
+    pub fn to_key(&self, round: u128) -> Option<RoundKey> {
+        let mut iter = self.current.iter().chain(self.previous.iter());
+        iter.find(|k| k.round == round).cloned()
+    }
+}
+
+/// Derive a symmetric key from application's static `secret` and round
+/// `r`, refer module documentation for the algorithm.
+pub fn derive_key(secret: &[u8], r: &Random) -> [u8; 32] {
+    let hk = Hkdf::<Sha256>::new(Some(r.randomness.as_bytes()), secret);
+
+    let mut info = KEY_INFO.to_vec();
+    info.extend_from_slice(&r.round.to_be_bytes());
+
+    let mut key = [0_u8; 32];
+    // 32 bytes is well within the limit of 255 * 32 bytes.
+    hk.expand(&info, &mut key).ok();
+    key
+}
+
+#[cfg(test)]
+#[path = "keys_test.rs"]
+mod keys_test;
@@ -0,0 +1,50 @@
+use crate::fixtures::new_random;
+
+use super::*;
+
+#[test]
+fn test_derive_key() {
+    let (r1, r2) = (new_random(1, 1), new_random(2, 2));
+    let k1 = derive_key(b"secret", &r1);
+    assert_eq!(k1, derive_key(b"secret", &r1));
+    assert_ne!(k1, derive_key(b"secret", &r2));
+    assert_ne!(k1, derive_key(b"other", &r1));
+
+    // round is bound to the key, along with randomness.
+    assert_ne!(k1, derive_key(b"secret", &new_random(3, 1)));
+
+    // hand derived.
+    let hk = Hkdf::<Sha256>::new(Some(&[1; 32]), b"secret");
+    let mut info = b"drand-rs-key-rotation".to_vec();
+    info.extend_from_slice(&1_u128.to_be_bytes());
+    let mut key = [0_u8; 32];
+    hk.expand(&info, &mut key).unwrap();
+    assert_eq!(k1, key);
+}
+
+#[test]
+fn test_key_ring() {
+    let mut ring = KeyRing::new(b"secret");
+    assert_eq!(ring.to_current(), None);
+    assert_eq!(ring.to_previous(), None);
+
+    let (r1, r2, r3) = (new_random(1, 1), new_random(2, 2), new_random(3, 3));
+    assert!(ring.rotate(&r1));
+    assert_eq!(ring.to_current().unwrap().round, 1);
+    assert_eq!(ring.to_previous(), None);
+
+    assert!(ring.rotate(&r2));
+    assert!(!ring.rotate(&r2));
+    assert!(!ring.rotate(&r1));
+    assert_eq!(ring.to_current().unwrap().round, 2);
+    assert_eq!(ring.to_previous().unwrap().round, 1);
+    assert_eq!(ring.to_key(1).unwrap().key, derive_key(b"secret", &r1));
+
+    assert!(ring.rotate(&r3));
+    assert_eq!(ring.to_previous().unwrap().round, 2);
+    assert_eq!(ring.to_key(1), None);
+
+    // keys are not printed.
+    let s = format!("{:?}", ring);
+    assert_eq!(s, "KeyRing<Some(RoundKey<3>),Some(RoundKey<2>)>");
+}
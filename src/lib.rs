@@ -2,33 +2,82 @@ use std::time;
 
 #[macro_use]
 mod util;
+pub mod analytics;
+mod archive;
+mod backoff;
+mod breaker;
+mod chunked;
 mod client;
+mod client_empty;
+mod clock;
+mod conffile;
 mod core;
+mod cross_check;
+pub mod draws;
 mod endpoints;
+mod events;
+#[cfg(test)]
+mod fixtures;
 mod http;
+mod ipfilter;
+pub mod keys;
+mod read_handle;
+pub mod relay_rest;
+mod relays;
+mod scheme;
+mod singleflight;
+mod statedir;
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
+mod transport;
+mod types;
 mod verify;
 
-pub use crate::client::Client;
-pub use crate::core::{Config, Error, Info, Random, Result};
+pub use crate::archive::{Archive, Retention};
+pub use crate::backoff::Backoff;
+pub use crate::breaker::{Breaker, CircuitState};
+pub use crate::chunked::ChunkedArchive;
+pub use crate::client::{Category, Client, Endpoint, PostProcessor};
+pub use crate::client_empty::{NullClient, OfflineClient};
+pub use crate::clock::{Clock, SystemClock};
+pub use crate::core::{
+    Aggregate, Agreement, BootPlan, BootReport, CatchUp, Config, EndpointCheck, Error, ErrorKind,
+    Hedge, Incident, Info, Migration, Random, Result, ReverifyReport, SecurityPolicy, Strictness,
+    Usage, Verdict, PROGRESS_ROUNDS,
+};
+pub use crate::cross_check::CrossCheck;
+pub use crate::events::{Event, Listener};
+pub use crate::http::{CacheHeaders, EndpointStats, Timings};
+pub use crate::ipfilter::{Cidr, IpFilter};
+pub use crate::read_handle::{ReadHandle, Subscription};
+pub use crate::relays::{Relay, Relays};
+pub use crate::scheme::{randomness_from_signature, Scheme};
+pub use crate::transport::SharedTransport;
+pub use crate::verify::{recompute_randomness, VerifyError};
+pub use crate::types::{
+    Chain, ChainHash, PublicKey, Randomness, Signature, MAINNET_CHAIN_HASH, QUICKNET_CHAIN_HASH,
+    TESTNET_CHAIN_HASH, TESTNET_QUICKNET_CHAIN_HASH,
+};
 
-const MAINNET_CHAIN_HASH: &'static str =
-    "8990e7a9aaed2ffed73dbd7092123d6f289930540d7651336225dc172e51b2ce";
-
-// Trait for DrandClient, must eventually move to Client type.
-trait DrandClient {
+/// Trait implemented by all drand clients. Alternative client backends
+/// can implement this trait and be used as `Box<dyn DrandClient>`.
+pub trait DrandClient {
     /// Returns parameters of the chain this client is connected to.
     /// The public key, when it started, and how frequently it updates.
     fn to_info(&self) -> Result<Info>;
 
     /// Return the most recent round of randomness that will be available
     /// at time for the current client.
-    fn round_at(&self, t: time::SystemTime) -> u128;
+    fn round_at(&self, t: time::SystemTime) -> Result<u128>;
 
     /// Returns a the randomness at `round` or an error.
-    /// Requesting round = 0 will return randomness for the most
+    /// Requesting round = None will return randomness for the most
     /// recent known round.
-    fn get(&self, round: u128) -> Result<Random>;
+    fn get(&mut self, round: Option<u128>) -> Result<Random>;
 
-    /// Returns new randomness as it becomes available.
-    fn watch(&self) -> Result<Box<dyn Iterator<Item = Result<Random>>>>;
+    /// Returns new randomness as it becomes available. Rounds are yielded
+    /// strictly in order, without repetition. Rounds that cannot be
+    /// served are yielded as a single [Error::MissingRounds] item carrying
+    /// the missing range, instead of silently jumping past them.
+    fn watch(&mut self) -> Result<Box<dyn Iterator<Item = Result<Random>> + '_>>;
 }
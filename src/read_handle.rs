@@ -0,0 +1,234 @@
+//! Module implement read-only handles to a client, refer [ReadHandle].
+
+use tokio::sync::watch;
+
+use std::{sync::Mutex, time};
+
+use crate::{DrandClient, Error, Event, Info, Random, Result};
+
+// verified state of a client, shared with its read handles.
+#[derive(Clone, Default)]
+pub(crate) struct Verified {
+    info: Option<Info>,
+    check_point: Option<Random>,
+    latest: Option<Random>,
+}
+
+// sending side of verified state, held by the client. Read handles hold
+// only the receiving side, so that subscriptions end once all clones of
+// the client are dropped.
+pub(crate) struct Broadcast {
+    tx: Mutex<watch::Sender<Verified>>,
+    rx: watch::Receiver<Verified>,
+}
+
+impl Broadcast {
+    pub(crate) fn new() -> Broadcast {
+        let (tx, rx) = watch::channel(Verified::default());
+        Broadcast {
+            tx: Mutex::new(tx),
+            rx,
+        }
+    }
+
+    pub(crate) fn is_info(&self) -> bool {
+        self.rx.borrow().info.is_some()
+    }
+
+    pub(crate) fn set_info(&self, info: Info) {
+        self.update(|val| val.info = Some(info));
+    }
+
+    // listener for client events, picks check_point and new rounds.
+    pub(crate) fn on_event(&self, event: &Event) {
+        let (r, check_point) = match event {
+            Event::NewRound(r) => (r, false),
+            Event::CheckPoint(r) => (r, true),
+            _ => return,
+        };
+        self.update(|val| {
+            if check_point {
+                val.check_point = Some(r.clone());
+            }
+            match val.latest.as_ref() {
+                Some(latest) if latest.round >= r.round => (),
+                _ => val.latest = Some(r.clone()),
+            }
+        });
+    }
+
+    pub(crate) fn to_handle(&self, name: &str) -> ReadHandle {
+        ReadHandle {
+            name: name.to_string(),
+            rx: self.rx.clone(),
+        }
+    }
+
+    fn update<F>(&self, f: F)
+    where
+        F: FnOnce(&mut Verified),
+    {
+        // updates are serialized by the lock, a poisoned lock drops them.
+        if let Ok(tx) = self.tx.lock() {
+            let mut val = self.rx.borrow().clone();
+            f(&mut val);
+            tx.broadcast(val).ok();
+        }
+    }
+}
+
+/// Read-only handle to a [Client], refer [Client::read_handle]. Handle
+/// serves the client's verified check_point and the latest round verified
+/// by the client, and yields new rounds as the client verifies them. It
+/// does not make network calls, nor can it add endpoints, change policy
+/// or otherwise mutate the client, making it suitable for handing over to
+/// plugins and untrusted modules within an application.
+///
+/// Rounds are served as verified, post-processors registered with the
+/// client are not applied, and a round pinned with the client is not
+/// served as the latest round.
+///
+/// [Client]: crate::Client
+/// [Client::read_handle]: crate::Client::read_handle
+#[derive(Clone)]
+pub struct ReadHandle {
+    name: String,
+    rx: watch::Receiver<Verified>,
+}
+
+impl ReadHandle {
+    /// Return name of the client, this handle reads from.
+    pub fn to_name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// Return the hash-info of the client's chain, fails with Invalid
+    /// error if the client is yet to boot.
+    pub fn to_info(&self) -> Result<Info> {
+        match self.rx.borrow().info.clone() {
+            Some(info) => Ok(info),
+            None => err_at!(Invalid, msg: format!("client {} not booted", self.name)),
+        }
+    }
+
+    /// Return the client's verified check_point, if any.
+    pub fn to_check_point(&self) -> Option<Random> {
+        self.rx.borrow().check_point.clone()
+    }
+
+    /// Return the latest round verified by the client, if any.
+    pub fn to_latest(&self) -> Option<Random> {
+        self.rx.borrow().latest.clone()
+    }
+
+    /// Get requested round, latest round if `round` is None. Only the
+    /// latest round and check_point are held by the handle, other rounds
+    /// fail with [Error::MissingRounds].
+    pub fn get(&self, round: Option<u128>) -> Result<Random> {
+        let val = self.rx.borrow();
+        let (latest, cp) = (val.latest.as_ref(), val.check_point.as_ref());
+        let r = match round {
+            Some(round) => latest.into_iter().chain(cp).find(|r| r.round == round),
+            None => latest.or(cp),
+        };
+        match (r, round) {
+            (Some(r), _) => Ok(r.clone()),
+            (None, Some(round)) => {
+                let prefix = format!("{}:{}", file!(), line!());
+                Err(Error::MissingRounds(prefix, round..=round))
+            }
+            (None, None) => err_at!(Invalid, msg: format!("client {} no round", self.name)),
+        }
+    }
+
+    /// Subscribe to rounds verified by the client, after the latest round.
+    /// Refer [Subscription].
+    pub fn subscribe(&self) -> Subscription {
+        let next = self.rx.borrow().latest.as_ref().map(|r| r.round + 1);
+        Subscription {
+            rx: self.rx.clone(),
+            next,
+            pending: None,
+        }
+    }
+}
+
+impl DrandClient for ReadHandle {
+    fn to_info(&self) -> Result<Info> {
+        ReadHandle::to_info(self)
+    }
+
+    fn round_at(&self, t: time::SystemTime) -> Result<u128> {
+        ReadHandle::to_info(self)?.round_at(t)
+    }
+
+    fn get(&mut self, round: Option<u128>) -> Result<Random> {
+        ReadHandle::get(self, round)
+    }
+
+    fn watch(&mut self) -> Result<Box<dyn Iterator<Item = Result<Random>> + '_>> {
+        Ok(Box::new(self.subscribe()))
+    }
+}
+
+/// Iterator over rounds verified by a client, refer [ReadHandle::subscribe].
+/// Blocks until the client verifies a new round, and ends once all clones
+/// of the client are dropped.
+///
+/// Rounds are yielded strictly in order. Rounds skipped by the client, or
+/// verified faster than they are consumed, are yielded as a single
+/// [Error::MissingRounds] gap, followed by the round after the gap.
+pub struct Subscription {
+    rx: watch::Receiver<Verified>,
+    // next round to yield, None till the first round is seen.
+    next: Option<u128>,
+    // round after a yielded gap.
+    pending: Option<Random>,
+}
+
+impl Subscription {
+    // latest round, if it is at or after the next round to yield.
+    fn to_next(&self) -> Option<Random> {
+        let latest = self.rx.borrow().latest.clone()?;
+        match self.next {
+            Some(next) if latest.round < next => None,
+            _ => Some(latest),
+        }
+    }
+}
+
+impl Iterator for Subscription {
+    type Item = Result<Random>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        use futures::executor::block_on;
+
+        if let Some(r) = self.pending.take() {
+            return Some(Ok(r));
+        }
+
+        let r = loop {
+            match self.to_next() {
+                Some(r) => break r,
+                None => {
+                    block_on(self.rx.recv())?;
+                }
+            }
+        };
+        let gap = self.next.filter(|next| *next < r.round);
+        self.next = Some(r.round + 1);
+        match gap {
+            Some(next) => {
+                let till = r.round - 1;
+                self.pending = Some(r);
+                let prefix = format!("{}:{}", file!(), line!());
+                Some(Err(Error::MissingRounds(prefix, next..=till)))
+            }
+            None => Some(Ok(r)),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "read_handle_test.rs"]
+mod read_handle_test;
@@ -0,0 +1,74 @@
+use std::thread;
+
+use crate::{fixtures::new_random, ChainHash};
+
+use super::*;
+
+#[test]
+fn test_read_handle() {
+    let broadcast = Broadcast::new();
+    let mut handle = broadcast.to_handle("test");
+    assert_eq!(handle.to_name(), "test");
+
+    // before boot.
+    assert!(handle.to_info().is_err());
+    assert!(handle.get(None).is_err());
+    assert_eq!(handle.to_check_point(), None);
+
+    let info = Info {
+        hash: ChainHash([1; 32]),
+        ..Info::default()
+    };
+    broadcast.set_info(info.clone());
+    assert!(broadcast.is_info());
+    broadcast.on_event(&Event::CheckPoint(new_random(10, 10)));
+    assert_eq!(handle.to_info().unwrap(), info);
+    assert_eq!(handle.get(None).unwrap(), new_random(10, 10));
+    assert_eq!(handle.to_check_point(), Some(new_random(10, 10)));
+
+    broadcast.on_event(&Event::NewRound(new_random(12, 12)));
+    // older rounds and other events are ignored.
+    broadcast.on_event(&Event::NewRound(new_random(11, 11)));
+    broadcast.on_event(&Event::Quarantine("https://relay".to_string()));
+    assert_eq!(handle.to_latest(), Some(new_random(12, 12)));
+    assert_eq!(DrandClient::get(&mut handle, None).unwrap(), new_random(12, 12));
+    assert_eq!(handle.get(Some(12)).unwrap(), new_random(12, 12));
+    assert_eq!(handle.get(Some(10)).unwrap(), new_random(10, 10));
+    match handle.get(Some(11)) {
+        Err(Error::MissingRounds(_, rounds)) => assert_eq!(rounds, 11..=11),
+        res => panic!("unexpected {:?}", res),
+    }
+
+    // handles are clones, of the same verified state.
+    let other = handle.clone();
+    broadcast.on_event(&Event::NewRound(new_random(13, 13)));
+    assert_eq!(other.to_latest(), Some(new_random(13, 13)));
+    assert_eq!(other.to_check_point(), Some(new_random(10, 10)));
+}
+
+#[test]
+fn test_subscription() {
+    let broadcast = Broadcast::new();
+    broadcast.on_event(&Event::CheckPoint(new_random(1, 1)));
+    let subscription = broadcast.to_handle("test").subscribe();
+
+    let reader = thread::spawn(move || subscription.collect::<Vec<Result<Random>>>());
+    for round in [2, 3, 6].iter() {
+        // let the reader catch up, so that no round is overwritten.
+        thread::sleep(time::Duration::from_millis(100));
+        broadcast.on_event(&Event::NewRound(new_random(*round, *round as u8)));
+    }
+    thread::sleep(time::Duration::from_millis(100));
+    // subscription ends once the client is dropped.
+    std::mem::drop(broadcast);
+
+    let items = reader.join().unwrap();
+    assert_eq!(items.len(), 4, "{:?}", items);
+    assert_eq!(items[0].as_ref().unwrap(), &new_random(2, 2));
+    assert_eq!(items[1].as_ref().unwrap(), &new_random(3, 3));
+    match &items[2] {
+        Err(Error::MissingRounds(_, rounds)) => assert_eq!(rounds, &(4..=5)),
+        res => panic!("unexpected {:?}", res),
+    }
+    assert_eq!(items[3].as_ref().unwrap(), &new_random(6, 6));
+}
@@ -0,0 +1,264 @@
+//! Module implement typed bindings for the drand relay http api, for
+//! applications that talk to relays directly, beyond what [Client] does.
+//!
+//! | route                                     | method                   |
+//! |-------------------------------------------|--------------------------|
+//! | `GET /chains`                             | [RelayRest::chains]      |
+//! | `GET /info`, `/{chain}/info`              | [RelayRest::info]        |
+//! | `GET /public/latest`                      | [RelayRest::latest]      |
+//! | `GET /public/{round}`                     | [RelayRest::round]       |
+//! | `GET /health`                             | [RelayRest::health]      |
+//! | `GET /v2/chains`                          | [RelayRest::chains_v2]   |
+//! | `GET /v2/beacons`                         | [RelayRest::beacons_v2]  |
+//! | `GET /v2/{chains,beacons}/{id}/info`      | [RelayRest::info_v2]     |
+//! | `GET /v2/{chains,beacons}/{id}/rounds/..` | [RelayRest::round_v2]    |
+//!
+//! Responses are returned as they are served, as [InfoResponse] and
+//! [BeaconResponse], and can be converted into [Info] and [Random]. They
+//! are not verified, use [Client] for that. Responses with status 404 for
+//! a round fail with [Error::MissingRounds], 425 with
+//! [Error::RoundNotYetAvailable], 429 with [Error::RateLimited], and any
+//! other non-success status with IOError.
+//!
+//! [Client]: crate::Client
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use std::convert::{TryFrom, TryInto};
+
+use crate::{
+    clock::SystemClock,
+    core::MAX_CONNS,
+    http::{check_rate_limit, check_round, new_http_client, InfoJson, RandomJson},
+    ChainHash, Error, Info, Random, Result,
+};
+
+/// Chain to address, in a relay serving several chains.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Selector {
+    /// Default chain of the relay.
+    Default,
+    /// Chain by its chain-hash.
+    Chain(ChainHash),
+    /// Chain by its beacon-id, like `quicknet`, only with v2 routes.
+    Beacon(String),
+}
+
+impl Selector {
+    // path prefix for v1 routes.
+    fn to_v1_prefix(&self) -> Result<String> {
+        match self {
+            Selector::Default => Ok(String::default()),
+            Selector::Chain(hash) => Ok(format!("/{}", hash)),
+            Selector::Beacon(id) => err_at!(Invalid, msg: format!("beacon {:?} with v1 api", id)),
+        }
+    }
+
+    // path prefix for v2 routes.
+    fn to_v2_prefix(&self) -> String {
+        match self {
+            Selector::Default => "/v2/beacons/default".to_string(),
+            Selector::Chain(hash) => format!("/v2/chains/{}", hash),
+            Selector::Beacon(id) => format!("/v2/beacons/{}", id),
+        }
+    }
+}
+
+/// Chain info, as served by `/info` and `/v2/.../info` routes.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct InfoResponse {
+    /// Public key of the drand-group, in hex.
+    pub public_key: String,
+    /// Period in seconds.
+    pub period: f64,
+    /// Genesis time, as seconds since UNIX_EPOCH.
+    pub genesis_time: u64,
+    /// Chain-hash, in hex.
+    #[serde(alias = "chain_hash")]
+    pub hash: String,
+    /// Group-hash, previous signature of the first round, in hex.
+    #[serde(rename = "groupHash", alias = "genesis_seed")]
+    pub group_hash: String,
+    /// Signature scheme, like `pedersen-bls-chained`.
+    #[serde(rename = "schemeID", alias = "scheme", default)]
+    pub scheme_id: Option<String>,
+    /// Beacon-id, served by v2 routes.
+    #[serde(default)]
+    pub beacon_id: Option<String>,
+}
+
+impl TryFrom<InfoResponse> for Info {
+    type Error = Error;
+
+    fn try_from(val: InfoResponse) -> Result<Info> {
+        let info = InfoJson {
+            public_key: val.public_key,
+            period: val.period,
+            genesis_time: val.genesis_time,
+            hash: val.hash,
+            group_hash: val.group_hash,
+        };
+        info.try_into()
+    }
+}
+
+/// Round of randomness, as served by `/public/..` and `/v2/.../rounds/..`
+/// routes.
+#[derive(Clone, Eq, PartialEq, Debug, Deserialize, Serialize)]
+pub struct BeaconResponse {
+    /// Round number.
+    pub round: u128,
+    /// Randomness in hex, not served by v2 routes.
+    #[serde(default)]
+    pub randomness: String,
+    /// Signature in hex.
+    pub signature: String,
+    /// Previous signature in hex, not served for unchained schemes.
+    #[serde(default)]
+    pub previous_signature: String,
+}
+
+/// Convert into randomness, refer [Random::validate] for validations.
+impl TryFrom<BeaconResponse> for Random {
+    type Error = Error;
+
+    fn try_from(val: BeaconResponse) -> Result<Random> {
+        let r = RandomJson {
+            round: val.round,
+            randomness: val.randomness,
+            signature: val.signature,
+            previous_signature: val.previous_signature,
+        };
+        let r: Random = r.try_into()?;
+        r.validate()?;
+        Ok(r)
+    }
+}
+
+/// Health of the relay, as served by `/health`. Relay is healthy when it
+/// is serving the expected round.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Deserialize, Serialize)]
+pub struct HealthResponse {
+    /// Latest round served by the relay.
+    pub current: u128,
+    /// Round expected as per the relay's clock.
+    pub expected: u128,
+}
+
+impl HealthResponse {
+    /// Return whether relay is serving the expected round.
+    pub fn is_healthy(&self) -> bool {
+        self.current >= self.expected
+    }
+}
+
+/// Typed client for the relay http api, refer [relay_rest](crate::relay_rest).
+#[derive(Clone)]
+pub struct RelayRest {
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl RelayRest {
+    /// Create a client for the relay at `base_url`, like
+    /// `https://api.drand.sh`.
+    pub fn new(base_url: &str) -> Result<RelayRest> {
+        let client = new_http_client(MAX_CONNS, None, None, None)?;
+        Ok(RelayRest::with_client(base_url, client))
+    }
+
+    /// Same as [RelayRest::new], using application's http `client`.
+    pub fn with_client(base_url: &str, client: reqwest::Client) -> RelayRest {
+        RelayRest {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client,
+        }
+    }
+
+    /// Return base url of the relay.
+    pub fn to_base_url(&self) -> String {
+        self.base_url.clone()
+    }
+
+    /// Return chain-hash of chains served by the relay.
+    pub fn chains(&self) -> Result<Vec<ChainHash>> {
+        let hashes: Vec<String> = self.get("/chains", None)?;
+        hashes.iter().map(|h| ChainHash::try_from(h.as_str())).collect()
+    }
+
+    /// Return info of the chain.
+    pub fn info(&self, chain: &Selector) -> Result<InfoResponse> {
+        self.get(&format!("{}/info", chain.to_v1_prefix()?), None)
+    }
+
+    /// Return latest round of the chain.
+    pub fn latest(&self, chain: &Selector) -> Result<BeaconResponse> {
+        self.get(&format!("{}/public/latest", chain.to_v1_prefix()?), None)
+    }
+
+    /// Return `round` of the chain.
+    pub fn round(&self, chain: &Selector, round: u128) -> Result<BeaconResponse> {
+        let path = format!("{}/public/{}", chain.to_v1_prefix()?, round);
+        self.get(&path, Some(round))
+    }
+
+    /// Return health of the relay. Unhealthy relays respond with status
+    /// 500 along with the same body, which is parsed as well, refer
+    /// [HealthResponse::is_healthy].
+    pub fn health(&self) -> Result<HealthResponse> {
+        use futures::executor::block_on;
+
+        let url = self.base_url.clone() + "/health";
+        let fut = async {
+            let resp = err_at!(IOError, self.client.get(url.as_str()).send().await)?;
+            check_rate_limit(&resp, &SystemClock)?;
+            err_at!(JsonParse, resp.json().await)
+        };
+        block_on(fut)
+    }
+
+    /// Return chain-hash of chains served by the relay, with v2 routes.
+    pub fn chains_v2(&self) -> Result<Vec<ChainHash>> {
+        let hashes: Vec<String> = self.get("/v2/chains", None)?;
+        hashes.iter().map(|h| ChainHash::try_from(h.as_str())).collect()
+    }
+
+    /// Return beacon-id of chains served by the relay, with v2 routes.
+    pub fn beacons_v2(&self) -> Result<Vec<String>> {
+        self.get("/v2/beacons", None)
+    }
+
+    /// Return info of the chain, with v2 routes.
+    pub fn info_v2(&self, chain: &Selector) -> Result<InfoResponse> {
+        self.get(&format!("{}/info", chain.to_v2_prefix()), None)
+    }
+
+    /// Return `round` of the chain with v2 routes, latest round if None.
+    pub fn round_v2(&self, chain: &Selector, round: Option<u128>) -> Result<BeaconResponse> {
+        let path = match round {
+            Some(round) => format!("{}/rounds/{}", chain.to_v2_prefix(), round),
+            None => format!("{}/rounds/latest", chain.to_v2_prefix()),
+        };
+        self.get(&path, round)
+    }
+
+    fn get<T: DeserializeOwned>(&self, path: &str, round: Option<u128>) -> Result<T> {
+        use futures::executor::block_on;
+
+        let url = self.base_url.clone() + path;
+        let fut = async {
+            let resp = err_at!(IOError, self.client.get(url.as_str()).send().await)?;
+            check_rate_limit(&resp, &SystemClock)?;
+            if let Some(round) = round {
+                check_round(&resp, round, &SystemClock)?;
+            }
+            let resp = err_at!(IOError, resp.error_for_status())?;
+            err_at!(JsonParse, resp.json().await)
+        };
+        block_on(fut)
+    }
+}
+
+#[cfg(test)]
+#[path = "relay_rest_test.rs"]
+mod relay_rest_test;
@@ -0,0 +1,93 @@
+use crate::fixtures::{mainnet_info_json, mainnet_info_json_v2};
+
+use super::*;
+
+#[test]
+fn test_selector() {
+    let hash = ChainHash::try_from(crate::QUICKNET_CHAIN_HASH).unwrap();
+
+    assert_eq!(Selector::Default.to_v1_prefix().unwrap(), "");
+    let prefix = Selector::Chain(hash).to_v1_prefix().unwrap();
+    assert_eq!(prefix, format!("/{}", crate::QUICKNET_CHAIN_HASH));
+    assert!(Selector::Beacon("quicknet".to_string()).to_v1_prefix().is_err());
+
+    assert_eq!(Selector::Default.to_v2_prefix(), "/v2/beacons/default");
+    let prefix = Selector::Chain(hash).to_v2_prefix();
+    assert_eq!(prefix, format!("/v2/chains/{}", crate::QUICKNET_CHAIN_HASH));
+    let prefix = Selector::Beacon("quicknet".to_string()).to_v2_prefix();
+    assert_eq!(prefix, "/v2/beacons/quicknet");
+}
+
+#[test]
+fn test_info_response() {
+    let mut v1 = mainnet_info_json();
+    v1["schemeID"] = serde_json::json!("pedersen-bls-chained");
+    v1["metadata"] = serde_json::json!({"beaconID": "default"});
+    let mut v2 = mainnet_info_json_v2();
+    v2["scheme"] = serde_json::json!("pedersen-bls-chained");
+    v2["beacon_id"] = serde_json::json!("default");
+    let v1: InfoResponse = serde_json::from_value(v1).unwrap();
+    let v2: InfoResponse = serde_json::from_value(v2).unwrap();
+    assert_eq!(v1.scheme_id.as_deref(), Some("pedersen-bls-chained"));
+    assert_eq!(v1.beacon_id, None);
+    assert_eq!(v2.beacon_id.as_deref(), Some("default"));
+    assert_eq!(v1.scheme_id, v2.scheme_id);
+
+    let x = Info::try_from(v1).unwrap();
+    let y = Info::try_from(v2.clone()).unwrap();
+    assert_eq!(x, y);
+    assert_eq!(hex::encode(&x.hash), crate::MAINNET_CHAIN_HASH);
+
+    let bad = InfoResponse {
+        public_key: "zz".to_string(),
+        ..v2
+    };
+    assert!(Info::try_from(bad).is_err());
+}
+
+#[test]
+fn test_beacon_response() {
+    let data = r#"{
+        "round": 1,
+        "signature": "8d61d9100567de44682506aea1a7a6fa6e5491cd27a0a0ed349ef6910ac5ac20ff7bc3e09d7c046566c9f7f3c6f3b10104990e7cb424998203d8f7de586fb7fa5f60045417a432684f85093b06ca91c769f0e7ca19268375e659c2a2352b4655",
+        "previous_signature": "176f93498eac9ca337150b46d21dd58673ea4e3581185f869672e59fa4cb390a"
+    }"#;
+    let resp: BeaconResponse = serde_json::from_str(data).unwrap();
+    assert_eq!(resp.randomness, "");
+    let r = Random::try_from(resp.clone()).unwrap();
+    assert_eq!(r.round, 1);
+    assert_eq!(
+        hex::encode(&r.randomness),
+        "101297f1ca7dc44ef6088d94ad5fb7ba03455dc33d53ddb412bbc4564ed986ec"
+    );
+
+    // randomness is validated against the signature.
+    let bad = BeaconResponse {
+        randomness: hex::encode([0; 32]),
+        ..resp.clone()
+    };
+    assert!(Random::try_from(bad).is_err());
+    let bad = BeaconResponse { round: 0, ..resp };
+    assert!(Random::try_from(bad).is_err());
+}
+
+#[test]
+fn test_health_response() {
+    let h: HealthResponse = serde_json::from_str(r#"{"current": 10, "expected": 10}"#).unwrap();
+    assert!(h.is_healthy());
+    let h: HealthResponse = serde_json::from_str(r#"{"current": 8, "expected": 10}"#).unwrap();
+    assert!(!h.is_healthy());
+}
+
+#[test]
+fn test_relay_rest() {
+    let rest = RelayRest::new("http://127.0.0.1:1/").unwrap();
+    assert_eq!(rest.to_base_url(), "http://127.0.0.1:1");
+
+    match rest.latest(&Selector::Default) {
+        Err(Error::IOError(_, _)) => (),
+        res => panic!("unexpected {:?}", res),
+    }
+    let beacon = Selector::Beacon("quicknet".to_string());
+    assert!(matches!(rest.info(&beacon), Err(Error::Invalid(_, _))));
+}
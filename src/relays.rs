@@ -0,0 +1,185 @@
+//! Module implement the list of known relays, shipped with the crate and
+//! updatable from a signed document.
+//!
+//! Relays operated by the League of Entropy come and go. Instead of
+//! waiting for a crate release, applications can load an updated list
+//! from a file or an url. Updated lists are wrapped in a signed document:
+//!
+//! ```json
+//! { "payload": "<relay-list as json string>", "signature": "<hex>" }
+//! ```
+//!
+//! where `signature` is the ed25519 signature over the exact bytes of
+//! `payload`, and `payload` is a relay-list of the form:
+//!
+//! ```json
+//! {
+//!     "version": 2,
+//!     "relays": [ { "url": "https://api.drand.sh", "category": "origin" } ]
+//! }
+//! ```
+//!
+//! The bundled list is compiled into the crate and trusted as is.
+
+use ed25519_dalek as ed25519;
+use serde::Deserialize;
+
+use std::{convert::TryFrom, fs, path};
+
+use crate::{client::Category, Endpoint, Error, Result};
+
+const BUNDLED_RELAYS: &str = include_str!("relays.json");
+
+/// A single relay from the relay-list.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Relay {
+    /// Base url of the relay, like `https://api.drand.sh`.
+    pub url: String,
+    /// Category of the relay.
+    pub category: Category,
+}
+
+/// Versioned list of relays.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Relays {
+    version: u64,
+    relays: Vec<Relay>,
+}
+
+impl Default for Relays {
+    fn default() -> Self {
+        Relays::bundled()
+    }
+}
+
+impl Relays {
+    /// Return the relay-list bundled with this crate.
+    pub fn bundled() -> Relays {
+        // bundled list is validated by test cases.
+        Relays::from_payload(BUNDLED_RELAYS).unwrap()
+    }
+
+    /// Load relay-list from `source`, which can be a file path or an
+    /// `http(s)://` url, and verify its signature with ed25519 public
+    /// `key`. If `source` is None, return the bundled relay-list.
+    ///
+    /// To prevent rollback to stale relays, loaded list is rejected if its
+    /// version is older than the bundled list.
+    pub fn load(source: Option<&str>, key: &[u8]) -> Result<Relays> {
+        let bundled = Relays::bundled();
+        let relays = match source {
+            None => return Ok(bundled),
+            Some(s) if s.starts_with("https://") || s.starts_with("http://") => {
+                Relays::from_url(s, key)?
+            }
+            Some(s) => Relays::from_file(s, key)?,
+        };
+
+        if relays.version < bundled.version {
+            let msg = format!(
+                "relay-list version {} older than bundled {}",
+                relays.version, bundled.version
+            );
+            err_at!(Invalid, msg: msg)?
+        }
+        Ok(relays)
+    }
+
+    /// Load signed relay-list from `file`, refer [Relays::from_signed].
+    pub fn from_file<P: AsRef<path::Path>>(file: P, key: &[u8]) -> Result<Relays> {
+        let data = err_at!(IOError, fs::read_to_string(file.as_ref()))?;
+        Relays::from_signed(&data, key)
+    }
+
+    /// Fetch signed relay-list from `url`, refer [Relays::from_signed].
+    pub fn from_url(url: &str, key: &[u8]) -> Result<Relays> {
+        use futures::executor::block_on;
+
+        let fut = async {
+            let resp = err_at!(IOError, reqwest::get(url).await)?;
+            let resp = err_at!(IOError, resp.error_for_status())?;
+            err_at!(IOError, resp.text().await)
+        };
+        Relays::from_signed(&block_on(fut)?, key)
+    }
+
+    /// Parse signed relay-list from `data`, and verify its signature with
+    /// ed25519 public `key`. Return NotSecure error if signature does not
+    /// match.
+    pub fn from_signed(data: &str, key: &[u8]) -> Result<Relays> {
+        let doc: SignedJson = err_at!(JsonParse, serde_json::from_str(data))?;
+
+        let key = err_at!(Invalid, ed25519::PublicKey::from_bytes(key))?;
+        let sig = err_at!(HexParse, hex::decode(&doc.signature))?;
+        let sig = err_at!(Invalid, ed25519::Signature::try_from(sig.as_slice()))?;
+        err_at!(NotSecure, key.verify_strict(doc.payload.as_bytes(), &sig))?;
+
+        Relays::from_payload(&doc.payload)
+    }
+
+    fn from_payload(payload: &str) -> Result<Relays> {
+        let val: RelaysJson = err_at!(JsonParse, serde_json::from_str(payload))?;
+
+        let mut relays = vec![];
+        for relay in val.relays.into_iter() {
+            let category = match relay.category.as_str() {
+                "origin" => Category::Origin,
+                "cache" => Category::Cache,
+                c => err_at!(Invalid, msg: format!("relay category {:?}", c))?,
+            };
+            relays.push(Relay {
+                url: relay.url.trim_end_matches('/').to_string(),
+                category,
+            });
+        }
+        Ok(Relays {
+            version: val.version,
+            relays,
+        })
+    }
+
+    /// Return the version of this relay-list.
+    pub fn to_version(&self) -> u64 {
+        self.version
+    }
+
+    /// Iterate over relays in this list.
+    pub fn iter(&self) -> impl Iterator<Item = &Relay> {
+        self.relays.iter()
+    }
+
+    /// Return relays as endpoints, that can be added to [Client].
+    ///
+    /// [Client]: crate::Client
+    pub fn to_endpoints(&self) -> Vec<Endpoint> {
+        self.relays
+            .iter()
+            .map(|r| Endpoint::Http {
+                url: r.url.clone(),
+                category: r.category,
+            })
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+struct SignedJson {
+    payload: String,
+    signature: String,
+}
+
+#[derive(Deserialize)]
+struct RelaysJson {
+    version: u64,
+    relays: Vec<RelayJson>,
+}
+
+#[derive(Deserialize)]
+struct RelayJson {
+    url: String,
+    category: String,
+}
+
+#[cfg(test)]
+#[path = "relays_test.rs"]
+mod relays_test;
@@ -0,0 +1,89 @@
+use ed25519_dalek::{ExpandedSecretKey, PublicKey, SecretKey};
+
+use super::*;
+
+fn sign(payload: &str, seed: u8) -> (String, Vec<u8>) {
+    let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+    let public = PublicKey::from(&secret);
+    let sig = ExpandedSecretKey::from(&secret).sign(payload.as_bytes(), &public);
+
+    let doc = serde_json::json!({
+        "payload": payload,
+        "signature": hex::encode(sig.to_bytes().as_ref()),
+    });
+    (doc.to_string(), public.as_bytes().to_vec())
+}
+
+#[test]
+fn test_relays_bundled() {
+    let relays = Relays::bundled();
+    assert_eq!(relays.to_version(), 1);
+    assert_eq!(relays.iter().count(), 4);
+    assert_eq!(relays.to_endpoints().len(), 4);
+
+    let key = [0_u8; 32];
+    assert_eq!(Relays::load(None, &key).unwrap(), relays);
+}
+
+#[test]
+fn test_relays_signed() {
+    let payload = r#"{
+        "version": 2,
+        "relays": [
+            { "url": "https://api.drand.sh/", "category": "origin" },
+            { "url": "https://drand.cloudflare.com", "category": "cache" }
+        ]
+    }"#;
+    let (doc, key) = sign(payload, 7);
+
+    let relays = Relays::from_signed(&doc, &key).unwrap();
+    assert_eq!(relays.to_version(), 2);
+    let items: Vec<Relay> = relays.iter().cloned().collect();
+    assert_eq!(
+        items,
+        vec![
+            Relay {
+                url: "https://api.drand.sh".to_string(),
+                category: Category::Origin
+            },
+            Relay {
+                url: "https://drand.cloudflare.com".to_string(),
+                category: Category::Cache
+            },
+        ]
+    );
+
+    // signed by a different key.
+    let (_, other) = sign(payload, 8);
+    match Relays::from_signed(&doc, &other) {
+        Err(Error::NotSecure(_, _)) => (),
+        res => panic!("unexpected {:?}", res.map(|r| r.to_version())),
+    }
+
+    // tampered payload.
+    let tampered = doc.replace("cloudflare", "cloudfiare");
+    assert!(Relays::from_signed(&tampered, &key).is_err());
+
+    // invalid category.
+    let (doc, key) = sign(&payload.replace("\"cache\"", "\"edge\""), 7);
+    assert!(Relays::from_signed(&doc, &key).is_err());
+}
+
+#[test]
+fn test_relays_load() {
+    let dir = std::env::temp_dir();
+
+    let payload = r#"{"version": 2, "relays": []}"#;
+    let (doc, key) = sign(payload, 9);
+    let file = dir.join("drand-relays-test-v2.json");
+    fs::write(&file, doc).unwrap();
+    let relays = Relays::load(file.to_str(), &key).unwrap();
+    assert_eq!(relays.to_version(), 2);
+
+    // rollback to older version is rejected.
+    let payload = r#"{"version": 0, "relays": []}"#;
+    let (doc, key) = sign(payload, 9);
+    let file = dir.join("drand-relays-test-v0.json");
+    fs::write(&file, doc).unwrap();
+    assert!(Relays::load(file.to_str(), &key).is_err());
+}
@@ -0,0 +1,120 @@
+//! Module implement message and randomness construction for drand's
+//! signing schemes.
+//!
+//! * Chained schemes sign `sha256(previous_signature || round)`, linking
+//!   every round to its predecessor.
+//! * Unchained schemes sign `sha256(round)`, rounds can be verified
+//!   independently of each other.
+//!
+//! In both cases round is encoded as 8-byte big-endian integer, and
+//! randomness is `sha256(signature)`.
+
+use sha2::{Digest, Sha256};
+
+use std::{convert::TryFrom, fmt, result, str::FromStr};
+
+use crate::{types::Randomness, Error, Result};
+
+/// Signing scheme of a drand chain, as advertised by the `schemeID` field
+/// of chain info.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Scheme {
+    /// `pedersen-bls-chained`, signatures on G2, used by mainnet.
+    PedersenBlsChained,
+    /// `pedersen-bls-unchained`, signatures on G2.
+    PedersenBlsUnchained,
+    /// `bls-unchained-on-g1`, signatures on G1, with non-standard DST.
+    BlsUnchainedOnG1,
+    /// `bls-unchained-g1-rfc9380`, signatures on G1, used by quicknet.
+    BlsUnchainedG1Rfc9380,
+}
+
+impl Default for Scheme {
+    fn default() -> Self {
+        Scheme::PedersenBlsChained
+    }
+}
+
+impl Scheme {
+    /// Return the scheme identifier, as in chain info.
+    pub fn as_id(&self) -> &'static str {
+        match self {
+            Scheme::PedersenBlsChained => "pedersen-bls-chained",
+            Scheme::PedersenBlsUnchained => "pedersen-bls-unchained",
+            Scheme::BlsUnchainedOnG1 => "bls-unchained-on-g1",
+            Scheme::BlsUnchainedG1Rfc9380 => "bls-unchained-g1-rfc9380",
+        }
+    }
+
+    /// Return whether rounds are linked to their previous signature.
+    pub fn is_chained(&self) -> bool {
+        matches!(self, Scheme::PedersenBlsChained)
+    }
+
+    /// Return the size of signature in bytes, 96 for G2 and 48 for G1.
+    pub fn to_signature_size(&self) -> usize {
+        match self {
+            Scheme::PedersenBlsChained | Scheme::PedersenBlsUnchained => 96,
+            Scheme::BlsUnchainedOnG1 | Scheme::BlsUnchainedG1Rfc9380 => 48,
+        }
+    }
+
+    /// Return the message signed for `round`. `previous_signature` is
+    /// ignored by unchained schemes.
+    pub fn message_for_round(&self, round: u128, previous_signature: &[u8]) -> Result<Vec<u8>> {
+        let round = err_at!(Invalid, u64::try_from(round), format!("round {}", round))?;
+
+        let mut hasher = Sha256::default();
+        if self.is_chained() {
+            hasher.update(previous_signature);
+        }
+        hasher.update(round.to_be_bytes());
+        Ok(hasher.finalize().to_vec())
+    }
+
+    /// Return the randomness derived from `signature`, after validating
+    /// its length for this scheme.
+    pub fn randomness_from_signature(&self, signature: &[u8]) -> Result<Randomness> {
+        if signature.len() != self.to_signature_size() {
+            let (n, m) = (signature.len(), self.to_signature_size());
+            err_at!(Invalid, msg: format!("{} signature length {} != {}", self, n, m))?
+        }
+        Ok(randomness_from_signature(signature))
+    }
+}
+
+impl fmt::Display for Scheme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+        write!(f, "{}", self.as_id())
+    }
+}
+
+impl FromStr for Scheme {
+    type Err = Error;
+
+    /// Parse scheme from its identifier.
+    fn from_str(s: &str) -> Result<Scheme> {
+        let schemes = [
+            Scheme::PedersenBlsChained,
+            Scheme::PedersenBlsUnchained,
+            Scheme::BlsUnchainedOnG1,
+            Scheme::BlsUnchainedG1Rfc9380,
+        ];
+        let s = s.trim().to_lowercase();
+        match schemes.iter().find(|sch| sch.as_id() == s) {
+            Some(scheme) => Ok(*scheme),
+            None => err_at!(Invalid, msg: format!("unknown scheme {:?}", s)),
+        }
+    }
+}
+
+/// Return the randomness derived from `signature`, same for all schemes.
+pub fn randomness_from_signature(signature: &[u8]) -> Randomness {
+    let mut bytes = [0_u8; Randomness::SIZE];
+    bytes.copy_from_slice(Sha256::digest(signature).as_slice());
+    Randomness(bytes)
+}
+
+#[cfg(test)]
+#[path = "scheme_test.rs"]
+mod scheme_test;
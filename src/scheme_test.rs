@@ -0,0 +1,81 @@
+use super::*;
+
+// mainnet, round 1.
+const SIGNATURE: &str = "8d61d9100567de44682506aea1a7a6fa6e5491cd27a0a0ed349ef6910ac5ac20ff7bc3e09d7c046566c9f7f3c6f3b10104990e7cb424998203d8f7de586fb7fa5f60045417a432684f85093b06ca91c769f0e7ca19268375e659c2a2352b4655";
+const PREVIOUS_SIGNATURE: &str = "176f93498eac9ca337150b46d21dd58673ea4e3581185f869672e59fa4cb390a";
+const RANDOMNESS: &str = "101297f1ca7dc44ef6088d94ad5fb7ba03455dc33d53ddb412bbc4564ed986ec";
+
+// quicknet, round 123.
+const QUICKNET_SIGNATURE: &str = "b75c69d0b72a5d906e854e808ba7e2accb1542ac355ae486d591aa9d43765482e26cd02df835d3546d23c4b13e0dfc92";
+const QUICKNET_RANDOMNESS: &str =
+    "fb8f7bc29bf24db51871ec8c79f3a1e4bd0557bc0dfcee9ed1d924e69d1c60dc";
+
+#[test]
+fn test_scheme_parse() {
+    let schemes = [
+        Scheme::PedersenBlsChained,
+        Scheme::PedersenBlsUnchained,
+        Scheme::BlsUnchainedOnG1,
+        Scheme::BlsUnchainedG1Rfc9380,
+    ];
+    for scheme in schemes.iter() {
+        assert_eq!(scheme.to_string().parse::<Scheme>().unwrap(), *scheme);
+    }
+    assert_eq!(Scheme::default(), Scheme::PedersenBlsChained);
+    assert_eq!(" Bls-Unchained-G1-RFC9380 ".parse::<Scheme>().unwrap(), schemes[3]);
+    assert!("bls-chained".parse::<Scheme>().is_err());
+}
+
+#[test]
+fn test_message_for_round() {
+    let prev = hex::decode(PREVIOUS_SIGNATURE).unwrap();
+
+    let msg = Scheme::PedersenBlsChained.message_for_round(1, &prev).unwrap();
+    assert_eq!(
+        hex::encode(&msg),
+        "3bdd496a35f3c3b6b4318da8b77d3ce4793cbdf26e08156f6fd8c49e85b727cb"
+    );
+
+    // unchained schemes ignore previous signature.
+    let unchained = [
+        Scheme::PedersenBlsUnchained,
+        Scheme::BlsUnchainedOnG1,
+        Scheme::BlsUnchainedG1Rfc9380,
+    ];
+    for scheme in unchained.iter() {
+        assert!(!scheme.is_chained());
+        let msg = scheme.message_for_round(1, &prev).unwrap();
+        assert_eq!(msg, scheme.message_for_round(1, &[]).unwrap());
+        assert_eq!(
+            hex::encode(&msg),
+            "cd2662154e6d76b2b2b92e70c0cac3ccf534f9b74eb5b89819ec509083d00a50"
+        );
+        let msg = scheme.message_for_round(1000, &[]).unwrap();
+        assert_eq!(
+            hex::encode(&msg),
+            "f652498d092acd949bad74e40683bf3824fb817980504a0c7e6722cfc5a9c0a3"
+        );
+    }
+
+    // round does not fit in 8 bytes.
+    let round = (u64::MAX as u128) + 1;
+    assert!(Scheme::PedersenBlsChained.message_for_round(round, &prev).is_err());
+}
+
+#[test]
+fn test_randomness_from_signature() {
+    let sig = hex::decode(SIGNATURE).unwrap();
+    let randomness = randomness_from_signature(&sig);
+    assert_eq!(randomness.to_string(), RANDOMNESS);
+
+    let scheme = Scheme::PedersenBlsChained;
+    assert_eq!(scheme.randomness_from_signature(&sig).unwrap(), randomness);
+
+    // G1 signatures are 48 bytes.
+    let scheme = Scheme::BlsUnchainedG1Rfc9380;
+    assert!(scheme.randomness_from_signature(&sig).is_err());
+    let sig = hex::decode(QUICKNET_SIGNATURE).unwrap();
+    let randomness = scheme.randomness_from_signature(&sig).unwrap();
+    assert_eq!(randomness.to_string(), QUICKNET_RANDOMNESS);
+    assert!(Scheme::PedersenBlsChained.randomness_from_signature(&sig).is_err());
+}
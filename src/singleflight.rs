@@ -0,0 +1,118 @@
+//! Module implement coalescing of concurrent calls for the same key.
+
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, Condvar, Mutex},
+};
+
+use crate::{Error, Result};
+
+// Group of in-flight calls. When several callers concurrently call for
+// the same key, only the first caller (leader) executes the call, rest
+// of them wait for the leader and share its result.
+pub(crate) struct Group<K, V> {
+    flights: Mutex<HashMap<K, Arc<Flight<V>>>>,
+}
+
+struct Flight<V> {
+    done: Mutex<Option<Result<V>>>,
+    cond: Condvar,
+}
+
+// completes the flight when dropped, with the leader's result, or with an
+// error if the leader panicked before it had a result, so that waiters do
+// not wait forever.
+struct Landing<'a, K, V>
+where
+    K: Eq + Hash,
+{
+    flights: &'a Mutex<HashMap<K, Arc<Flight<V>>>>,
+    key: &'a K,
+    flight: &'a Flight<V>,
+    res: Option<Result<V>>,
+}
+
+impl<'a, K, V> Drop for Landing<'a, K, V>
+where
+    K: Eq + Hash,
+{
+    fn drop(&mut self) {
+        let res = match self.res.take() {
+            Some(res) => res,
+            None => err_at!(Fatal, msg: format!("leader of the call aborted")),
+        };
+        // locks are not held while calling out, recover from poisoning.
+        match self.flights.lock() {
+            Ok(mut flights) => flights.remove(self.key),
+            Err(poisoned) => poisoned.into_inner().remove(self.key),
+        };
+        let mut done = match self.flight.done.lock() {
+            Ok(done) => done,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        *done = Some(res);
+        self.flight.cond.notify_all();
+    }
+}
+
+impl<K, V> Default for Group<K, V>
+where
+    K: Eq + Hash,
+{
+    fn default() -> Self {
+        Group {
+            flights: Mutex::new(HashMap::default()),
+        }
+    }
+}
+
+impl<K, V> Group<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    pub(crate) fn call<F>(&self, key: K, f: F) -> Result<V>
+    where
+        F: FnOnce() -> Result<V>,
+    {
+        let (flight, leader) = {
+            let mut flights = err_at!(PoisonedLock, self.flights.lock())?;
+            match flights.get(&key) {
+                Some(flight) => (Arc::clone(flight), false),
+                None => {
+                    let flight = Arc::new(Flight {
+                        done: Mutex::new(None),
+                        cond: Condvar::new(),
+                    });
+                    flights.insert(key.clone(), Arc::clone(&flight));
+                    (flight, true)
+                }
+            }
+        };
+
+        if leader {
+            let mut landing = Landing {
+                flights: &self.flights,
+                key: &key,
+                flight: &flight,
+                res: None,
+            };
+            let res = f();
+            landing.res = Some(res.clone());
+            res
+        } else {
+            let mut done = err_at!(PoisonedLock, flight.done.lock())?;
+            loop {
+                if let Some(res) = done.as_ref() {
+                    break res.clone();
+                }
+                done = err_at!(PoisonedLock, flight.cond.wait(done))?;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "singleflight_test.rs"]
+mod singleflight_test;
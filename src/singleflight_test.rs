@@ -0,0 +1,60 @@
+use std::{
+    sync::{atomic::AtomicUsize, atomic::Ordering::SeqCst, Barrier},
+    thread, time,
+};
+
+use super::*;
+
+#[test]
+fn test_singleflight() {
+    let group: Arc<Group<Option<u128>, u128>> = Arc::new(Group::default());
+    let calls = Arc::new(AtomicUsize::new(0));
+    let barrier = Arc::new(Barrier::new(4));
+
+    let mut handles = vec![];
+    for _ in 0..4 {
+        let (group, calls, barrier) = (group.clone(), calls.clone(), barrier.clone());
+        handles.push(thread::spawn(move || {
+            barrier.wait();
+            group.call(Some(10), || {
+                calls.fetch_add(1, SeqCst);
+                thread::sleep(time::Duration::from_millis(500));
+                Ok(10)
+            })
+        }));
+    }
+    for handle in handles.into_iter() {
+        assert_eq!(handle.join().unwrap().unwrap(), 10);
+    }
+    assert_eq!(calls.load(SeqCst), 1);
+
+    // once the flight is over, next call is executed afresh.
+    let res = group.call(Some(10), || err_at!(Invalid, msg: format!("fail")));
+    assert!(res.is_err());
+    assert_eq!(group.call(None, || Ok(20)).unwrap(), 20);
+}
+
+#[test]
+fn test_singleflight_leader_panic() {
+    let group: Arc<Group<u128, u128>> = Arc::new(Group::default());
+    let barrier = Arc::new(Barrier::new(2));
+
+    let leader = {
+        let (group, barrier) = (group.clone(), barrier.clone());
+        thread::spawn(move || {
+            group.call(1, || {
+                barrier.wait();
+                thread::sleep(time::Duration::from_millis(200));
+                panic!("leader failed")
+            })
+        })
+    };
+    barrier.wait();
+    // waiter fails instead of hanging on the aborted leader.
+    match group.call(1, || Ok(10)) {
+        Err(Error::Fatal(_, _)) => (),
+        res => panic!("unexpected {:?}", res),
+    }
+    assert!(leader.join().is_err());
+    assert_eq!(group.call(1, || Ok(10)).unwrap(), 10);
+}
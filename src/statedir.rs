@@ -0,0 +1,136 @@
+//! Module implement persistence of client state into a directory, refer
+//! [Config::state_dir].
+//!
+//! Directory holds chain info, latest verified check_point and latency
+//! stats of endpoints, as json files. Files are written atomically, to a
+//! temporary file that is then renamed, so that a crash never leaves a
+//! partially written state behind.
+//!
+//! [Config::state_dir]: crate::Config::state_dir
+
+use std::{
+    collections::BTreeMap,
+    convert::TryInto,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicU64, Ordering::SeqCst},
+    time,
+};
+
+use crate::{
+    http::{InfoJson, RandomJson},
+    Info, Random, Result,
+};
+
+const INFO_FILE: &str = "info.json";
+const CHECK_POINT_FILE: &str = "check_point.json";
+const STATS_FILE: &str = "stats.json";
+
+// Latency samples for each endpoint, keyed by endpoint's base url.
+pub(crate) type Samples = BTreeMap<String, Vec<time::Duration>>;
+
+#[derive(Clone)]
+pub(crate) struct StateDir {
+    dir: PathBuf,
+}
+
+impl StateDir {
+    pub(crate) fn new(dir: PathBuf) -> StateDir {
+        StateDir { dir }
+    }
+
+    pub(crate) fn load_info(&self) -> Result<Option<Info>> {
+        match self.read(INFO_FILE)? {
+            Some(data) => {
+                let info: InfoJson = err_at!(JsonParse, serde_json::from_str(&data))?;
+                Ok(Some(info.try_into()?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub(crate) fn load_check_point(&self) -> Result<Option<Random>> {
+        match self.read(CHECK_POINT_FILE)? {
+            Some(data) => {
+                let r: RandomJson = err_at!(JsonParse, serde_json::from_str(&data))?;
+                Ok(Some(r.try_into()?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub(crate) fn load_stats(&self) -> Result<Samples> {
+        let micros: BTreeMap<String, Vec<u64>> = match self.read(STATS_FILE)? {
+            Some(data) => err_at!(JsonParse, serde_json::from_str(&data))?,
+            None => BTreeMap::default(),
+        };
+        let iter = micros.into_iter().map(|(url, samples)| {
+            let samples = samples.into_iter().map(time::Duration::from_micros);
+            (url, samples.collect())
+        });
+        Ok(iter.collect())
+    }
+
+    pub(crate) fn save(&self, info: &Info, cp: Option<&Random>, stats: &Samples) -> Result<()> {
+        err_at!(IOError, fs::create_dir_all(&self.dir))?;
+
+        let data = err_at!(JsonParse, serde_json::to_string(&InfoJson::from(info)))?;
+        self.write(INFO_FILE, data.as_bytes())?;
+
+        if let Some(cp) = cp {
+            let data = err_at!(JsonParse, serde_json::to_string(&RandomJson::from(cp)))?;
+            self.write(CHECK_POINT_FILE, data.as_bytes())?;
+        }
+
+        let micros: BTreeMap<&String, Vec<u64>> = stats
+            .iter()
+            .map(|(url, samples)| {
+                let samples = samples.iter().map(|s| s.as_micros() as u64);
+                (url, samples.collect())
+            })
+            .collect();
+        let data = err_at!(JsonParse, serde_json::to_string(&micros))?;
+        self.write(STATS_FILE, data.as_bytes())
+    }
+
+    fn read(&self, name: &str) -> Result<Option<String>> {
+        let file = self.dir.join(name);
+        match file.exists() {
+            true => Ok(Some(err_at!(IOError, fs::read_to_string(&file))?)),
+            false => Ok(None),
+        }
+    }
+
+    fn write(&self, name: &str, data: &[u8]) -> Result<()> {
+        write_atomic(&self.dir.join(name), data)
+    }
+}
+
+// write `data` to a temporary file next to `file`, and rename it to `file`.
+// Temporary files are named uniquely, by process-id and a counter, so that
+// concurrent writers to the same file do not clobber each other's writes.
+pub(crate) fn write_atomic(file: &Path, data: &[u8]) -> Result<()> {
+    static SEQNO: AtomicU64 = AtomicU64::new(0);
+
+    let mut name = file.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.{}.tmp", process::id(), SEQNO.fetch_add(1, SeqCst)));
+    let tmp = file.with_file_name(name);
+
+    let res = write_synced(&tmp, data).and_then(|_| err_at!(IOError, fs::rename(&tmp, file)));
+    if res.is_err() {
+        fs::remove_file(&tmp).ok();
+    }
+    res
+}
+
+fn write_synced(file: &Path, data: &[u8]) -> Result<()> {
+    let mut fd = err_at!(IOError, fs::File::create(file))?;
+    err_at!(IOError, fd.write_all(data))?;
+    err_at!(IOError, fd.sync_all())
+}
+
+#[cfg(test)]
+#[path = "statedir_test.rs"]
+mod statedir_test;
@@ -0,0 +1,95 @@
+use crate::{fixtures::mainnet, ChainHash, Client, Config};
+
+use super::*;
+
+#[test]
+fn test_state_dir() {
+    let dir = std::env::temp_dir().join("drand-rs-test-state-dir");
+    fs::remove_dir_all(&dir).ok();
+    let state_dir = StateDir::new(dir.clone());
+
+    assert_eq!(state_dir.load_info().unwrap(), None);
+    assert_eq!(state_dir.load_check_point().unwrap(), None);
+    assert!(state_dir.load_stats().unwrap().is_empty());
+
+    let (info, r1) = mainnet();
+    let mut stats = Samples::default();
+    let samples = vec![time::Duration::from_millis(120), time::Duration::from_micros(80)];
+    stats.insert("https://api.drand.sh".to_string(), samples);
+    state_dir.save(&info, Some(&r1), &stats).unwrap();
+
+    assert_eq!(state_dir.load_info().unwrap(), Some(info));
+    assert_eq!(state_dir.load_check_point().unwrap(), Some(r1));
+    assert_eq!(state_dir.load_stats().unwrap(), stats);
+    // temporary files are renamed.
+    for entry in fs::read_dir(&dir).unwrap() {
+        let name = entry.unwrap().file_name();
+        assert!(!name.to_string_lossy().ends_with(".tmp"), "{:?}", name);
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_state_dir_config() {
+    let dir = std::env::temp_dir().join("drand-rs-test-state-dir-config");
+    fs::remove_dir_all(&dir).ok();
+
+    let (info, r1) = mainnet();
+    let stats = Samples::default();
+    StateDir::new(dir.clone()).save(&info, Some(&r1), &stats).unwrap();
+
+    // persisted info and check_point allow an instant boot.
+    let mut config = Config::default();
+    config.set_state_dir(Some(dir.clone()));
+    let mut client = Client::from_config("test", config);
+    assert_eq!(client.to_info().unwrap(), info);
+    assert!(client.boot(Some(info.hash.as_bytes().to_vec())).is_ok());
+
+    // state persisted for a different chain is ignored.
+    let other = Info {
+        hash: ChainHash([1; 32]),
+        ..info.clone()
+    };
+    let mut config = Config::default();
+    config
+        .set_state_dir(Some(dir.clone()))
+        .set_info(Some(other.clone()));
+    let mut client = Client::from_config("test", config);
+    assert_eq!(client.to_info().unwrap(), other);
+    // without a check_point, boot goes to the network, that has no endpoints.
+    assert!(client.boot(None).is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_write_atomic() {
+    use std::{sync::Arc, thread};
+
+    let dir = std::env::temp_dir().join("drand-rs-test-write-atomic");
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+    let file = Arc::new(dir.join("data.json"));
+
+    // concurrent writers, last rename wins, with a complete write.
+    let writers: Vec<_> = (0..8_u8)
+        .map(|i| {
+            let file = Arc::clone(&file);
+            thread::spawn(move || write_atomic(&file, &[i; 4096]))
+        })
+        .collect();
+    for w in writers.into_iter() {
+        w.join().unwrap().unwrap();
+    }
+    let data = fs::read(file.as_ref()).unwrap();
+    assert_eq!(data.len(), 4096);
+    assert!(data.iter().all(|b| *b == data[0]));
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+    // failed write leaves no temporary file behind.
+    assert!(write_atomic(&dir.join("missing").join("data.json"), b"x").is_err());
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
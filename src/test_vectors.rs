@@ -0,0 +1,124 @@
+//! Module implement known-good beacons, for smoke-testing a build and
+//! alternate verification backends without network access. Enabled with
+//! the `test-vectors` feature.
+//!
+//! ```ignore
+//! use drand::test_vectors;
+//!
+//! test_vectors::verify_all()?;
+//! for v in test_vectors::VECTORS.iter() {
+//!     v.verify_with(|pk, prev, r| my_backend::verify(pk, prev, r))?;
+//! }
+//! ```
+//!
+//! [VECTORS] are limited to chained schemes, verifiable by this crate.
+//! Beacons from chains this crate cannot verify are listed in [DATA_ONLY],
+//! as served by relays, for backends that support their scheme.
+
+use std::convert::TryFrom;
+
+use crate::{verify::verify_chain, Chain, PublicKey, Random, Randomness, Result, Signature};
+
+/// A known-good beacon, fields are hex encoded.
+#[derive(Clone, Debug)]
+pub struct TestVector {
+    /// Chain that produced this beacon.
+    pub chain: Chain,
+    /// Chain's public key.
+    pub public_key: &'static str,
+    /// Round number of the beacon.
+    pub round: u128,
+    /// Signature of the previous round, group-hash for round 1. Empty for
+    /// unchained schemes.
+    pub previous_signature: &'static str,
+    /// Signature of the beacon, by the chain's public key.
+    pub signature: &'static str,
+    /// Randomness of the beacon, sha256 of its signature.
+    pub randomness: &'static str,
+}
+
+/// Mainnet, round 1.
+pub const MAINNET_ROUND_1: TestVector = TestVector {
+    chain: Chain::Mainnet,
+    public_key: "868f005eb8e6e4ca0a47c8a77ceaa5309a47978a7c71bc5cce96366b5d7a569937c529eeda66c7293784a9402801af31",
+    round: 1,
+    previous_signature: "176f93498eac9ca337150b46d21dd58673ea4e3581185f869672e59fa4cb390a",
+    signature: "8d61d9100567de44682506aea1a7a6fa6e5491cd27a0a0ed349ef6910ac5ac20ff7bc3e09d7c046566c9f7f3c6f3b10104990e7cb424998203d8f7de586fb7fa5f60045417a432684f85093b06ca91c769f0e7ca19268375e659c2a2352b4655",
+    randomness: "101297f1ca7dc44ef6088d94ad5fb7ba03455dc33d53ddb412bbc4564ed986ec",
+};
+
+/// Mainnet, round 72785, chained to the signature of round 72784.
+pub const MAINNET_ROUND_72785: TestVector = TestVector {
+    chain: Chain::Mainnet,
+    public_key: "868f005eb8e6e4ca0a47c8a77ceaa5309a47978a7c71bc5cce96366b5d7a569937c529eeda66c7293784a9402801af31",
+    round: 72785,
+    previous_signature: "a609e19a03c2fcc559e8dae14900aaefe517cb55c840f6e69bc8e4f66c8d18e8a609685d9917efbfb0c37f058c2de88f13d297c7e19e0ab24813079efe57a182554ff054c7638153f9b26a60e7111f71a0ff63d9571704905d3ca6df0b031747",
+    signature: "82f5d3d2de4db19d40a6980e8aa37842a0e55d1df06bd68bddc8d60002e8e959eb9cfa368b3c1b77d18f02a54fe047b80f0989315f83b12a74fd8679c4f12aae86eaf6ab5690b34f1fddd50ee3cc6f6cdf59e95526d5a5d82aaa84fa6f181e42",
+    randomness: "8b676484b5fb1f37f9ec5c413d7d29883504e5b669f604a1ce68b3388e9ae3d9",
+};
+
+/// Quicknet, round 123. Unchained, signed on G1 with the public key on
+/// G2, hence not verifiable by this crate, refer [DATA_ONLY].
+pub const QUICKNET_ROUND_123: TestVector = TestVector {
+    chain: Chain::Quicknet,
+    public_key: "83cf0f2896adee7eb8b5f01fcad3912212c437e0073e911fb90022d3e760183c8c4b450b6a0a6c3ac6a5776a2d1064510d1fec758c921cc22b0e17e63aaf4bcb5ed66304de9cf809bd274ca73bab4af5a6e9c76a4bc09e76eae8991ef5ece45a",
+    round: 123,
+    previous_signature: "",
+    signature: "b75c69d0b72a5d906e854e808ba7e2accb1542ac355ae486d591aa9d43765482e26cd02df835d3546d23c4b13e0dfc92",
+    randomness: "fb8f7bc29bf24db51871ec8c79f3a1e4bd0557bc0dfcee9ed1d924e69d1c60dc",
+};
+
+/// All known-good beacons, verifiable by this crate.
+pub const VECTORS: [TestVector; 2] = [MAINNET_ROUND_1, MAINNET_ROUND_72785];
+
+/// Known-good beacons of chains this crate cannot verify. Only their hex
+/// fields are to be used, [TestVector]'s methods fail for them.
+pub const DATA_ONLY: [TestVector; 1] = [QUICKNET_ROUND_123];
+
+impl TestVector {
+    /// Return the chain's public key.
+    pub fn to_public_key(&self) -> Result<PublicKey> {
+        PublicKey::try_from(self.public_key)
+    }
+
+    /// Return the previous signature, that this beacon is chained to.
+    pub fn to_previous_signature(&self) -> Result<Vec<u8>> {
+        err_at!(HexParse, hex::decode(self.previous_signature))
+    }
+
+    /// Return the beacon, after validating its fields.
+    pub fn to_random(&self) -> Result<Random> {
+        let r = Random {
+            round: self.round,
+            randomness: Randomness::try_from(self.randomness)?,
+            signature: Signature::try_from(self.signature)?,
+            previous_signature: self.to_previous_signature()?,
+        };
+        r.validate()?;
+        Ok(r)
+    }
+
+    /// Verify the beacon with this crate's verifier.
+    pub fn verify(&self) -> Result<()> {
+        self.verify_with(verify_chain)
+    }
+
+    /// Verify the beacon with `verifier`, called with the chain's public
+    /// key, the previous signature, and the beacon.
+    pub fn verify_with<F>(&self, verifier: F) -> Result<()>
+    where
+        F: Fn(&PublicKey, &[u8], &Random) -> Result<()>,
+    {
+        let (pk, prev) = (self.to_public_key()?, self.to_previous_signature()?);
+        verifier(&pk, &prev, &self.to_random()?)
+    }
+}
+
+/// Verify all known-good beacons with this crate's verifier.
+pub fn verify_all() -> Result<()> {
+    VECTORS.iter().map(TestVector::verify).collect()
+}
+
+#[cfg(test)]
+#[path = "test_vectors_test.rs"]
+mod test_vectors_test;
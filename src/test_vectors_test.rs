@@ -0,0 +1,59 @@
+use sha2::{Digest, Sha256};
+
+use crate::Error;
+
+use super::*;
+
+#[test]
+fn test_vectors() {
+    verify_all().unwrap();
+
+    let r = MAINNET_ROUND_1.to_random().unwrap();
+    assert_eq!(r.round, 1);
+    let info_pk = MAINNET_ROUND_1.to_public_key().unwrap();
+    MAINNET_ROUND_1
+        .verify_with(|pk, _, r1| {
+            assert_eq!(pk, &info_pk);
+            assert_eq!(r1, &r);
+            Ok(())
+        })
+        .unwrap();
+
+    // tampered vectors fail.
+    let bad = TestVector {
+        round: 2,
+        ..MAINNET_ROUND_1
+    };
+    assert!(matches!(bad.verify(), Err(Error::Verify(_, _))));
+    let bad = TestVector {
+        randomness: MAINNET_ROUND_1.previous_signature,
+        ..MAINNET_ROUND_1
+    };
+    assert!(bad.verify().is_err());
+}
+
+#[test]
+fn test_vectors_chained() {
+    let r = MAINNET_ROUND_72785.to_random().unwrap();
+    assert_eq!(r.round, 72785);
+    MAINNET_ROUND_72785.verify().unwrap();
+
+    // chained to the previous round's signature.
+    let bad = TestVector {
+        previous_signature: MAINNET_ROUND_1.signature,
+        ..MAINNET_ROUND_72785
+    };
+    assert!(matches!(bad.verify(), Err(Error::Verify(_, _))));
+}
+
+#[test]
+fn test_vectors_data_only() {
+    for v in DATA_ONLY.iter() {
+        let sig = hex::decode(v.signature).unwrap();
+        assert_eq!(hex::encode(Sha256::digest(&sig)), v.randomness);
+        // keys on G2 and signatures on G1 are not supported by this crate.
+        assert!(v.to_public_key().is_err());
+        assert!(v.to_random().is_err());
+        assert!(v.verify().is_err());
+    }
+}
@@ -0,0 +1,61 @@
+//! Module implement transport shared by several clients.
+
+use tokio::sync::Semaphore;
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    http::{new_http_client, Stats},
+    Error, Result,
+};
+
+/// Transport shared by several clients, refer [Config::transport].
+///
+/// Applications that construct several clients, like one per chain, can
+/// share a single connection pool, per endpoint latency stats and the
+/// limit on concurrent requests. Shared transport is a handle, clones
+/// share the same pool. Requests carry the user agent of the client
+/// sending them.
+///
+/// [Config::transport]: crate::Config::transport
+#[derive(Clone, Debug)]
+pub struct SharedTransport {
+    client: reqwest::Client,
+    limit: Option<Arc<Semaphore>>,
+    stats: Arc<Mutex<HashMap<String, Stats>>>,
+}
+
+impl SharedTransport {
+    /// Create a new transport, with at most `max_conns` idle connections
+    /// per remote, and at most `concurrency` concurrent requests across
+    /// all clients sharing this transport.
+    pub fn new(max_conns: usize, concurrency: Option<usize>) -> Result<SharedTransport> {
+        let val = SharedTransport {
+            client: new_http_client(max_conns, None, None, None)?,
+            limit: concurrency.map(|n| Arc::new(Semaphore::new(n))),
+            stats: Arc::new(Mutex::new(HashMap::default())),
+        };
+        Ok(val)
+    }
+
+    pub(crate) fn to_client(&self) -> reqwest::Client {
+        self.client.clone()
+    }
+
+    pub(crate) fn to_limit(&self) -> Option<Arc<Semaphore>> {
+        self.limit.clone()
+    }
+
+    // latency stats for endpoint at `url`, shared by all clients.
+    pub(crate) fn to_stats(&self, url: &str) -> Result<Stats> {
+        let mut stats = err_at!(PoisonedLock, self.stats.lock())?;
+        Ok(Arc::clone(stats.entry(url.to_string()).or_default()))
+    }
+}
+
+#[cfg(test)]
+#[path = "transport_test.rs"]
+mod transport_test;
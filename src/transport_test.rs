@@ -0,0 +1,14 @@
+use super::*;
+
+#[test]
+fn test_shared_transport() {
+    let transport = SharedTransport::new(4, Some(8)).unwrap();
+    assert!(transport.to_limit().is_some());
+    assert!(SharedTransport::new(4, None).unwrap().to_limit().is_none());
+
+    let a = transport.to_stats("https://api.drand.sh").unwrap();
+    let b = transport.clone().to_stats("https://api.drand.sh").unwrap();
+    let c = transport.to_stats("https://drand.cloudflare.com").unwrap();
+    assert!(Arc::ptr_eq(&a, &b));
+    assert!(!Arc::ptr_eq(&a, &c));
+}
@@ -0,0 +1,204 @@
+//! Module implement fixed size types used by [Info] and [Random].
+//!
+//! Length of each type is validated while parsing, so that use sites can
+//! rely on them without re-validation.
+//!
+//! [Info]: crate::Info
+//! [Random]: crate::Random
+
+use std::{convert::TryFrom, fmt, result, str::FromStr};
+
+use crate::{Error, Result};
+
+macro_rules! fixed_bytes {
+    ($(#[$doc:meta])* $name:ident, $size:expr) => {
+        $(#[$doc])*
+        #[derive(Clone, Copy, Eq, PartialEq, Hash)]
+        pub struct $name(pub [u8; $size]);
+
+        impl $name {
+            /// Size of this type in bytes.
+            pub const SIZE: usize = $size;
+
+            pub fn as_bytes(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                $name([0_u8; $size])
+            }
+        }
+
+        impl AsRef<[u8]> for $name {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl From<$name> for [u8; $size] {
+            fn from(val: $name) -> Self {
+                val.0
+            }
+        }
+
+        impl TryFrom<&[u8]> for $name {
+            type Error = Error;
+
+            fn try_from(val: &[u8]) -> Result<Self> {
+                if val.len() != $size {
+                    let name = stringify!($name);
+                    let msg = format!("{} length {} != {}", name, val.len(), $size);
+                    err_at!(Invalid, msg: msg)?
+                }
+                let mut bytes = [0_u8; $size];
+                bytes.copy_from_slice(val);
+                Ok($name(bytes))
+            }
+        }
+
+        impl TryFrom<&str> for $name {
+            type Error = Error;
+
+            fn try_from(val: &str) -> Result<Self> {
+                let bytes = err_at!(HexParse, hex::decode(val))?;
+                $name::try_from(bytes.as_slice())
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+                write!(f, "{}", hex::encode(&self.0))
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+                write!(f, "{}({})", stringify!($name), hex::encode(&self.0))
+            }
+        }
+    };
+}
+
+fixed_bytes!(
+    /// Distributed public key of the drand-group, compressed G1 point.
+    PublicKey,
+    48
+);
+
+fixed_bytes!(
+    /// Boneh-Lynn-Shacham (BLS) signature, compressed G2 point.
+    Signature,
+    96
+);
+
+fixed_bytes!(
+    /// Randomness, SHA-256 hash of the signature.
+    Randomness,
+    32
+);
+
+fixed_bytes!(
+    /// Chain-hash, uniquely identifies a drand chain.
+    ChainHash,
+    32
+);
+
+/// Chain-hash of drand mainnet, the default chain, 30s period.
+pub const MAINNET_CHAIN_HASH: &str =
+    "8990e7a9aaed2ffed73dbd7092123d6f289930540d7651336225dc172e51b2ce";
+
+/// Chain-hash of drand quicknet, unchained rounds with 3s period.
+pub const QUICKNET_CHAIN_HASH: &str =
+    "52db9ba70e0cc0f6eaf7803dd07447a1f5477735fd3f661792ba94600c84e971";
+
+/// Chain-hash of drand testnet, the default chain.
+pub const TESTNET_CHAIN_HASH: &str =
+    "84b2234fb34e835dccd048255d7ad3194b81af7d978c3bf157e3469592ae4e02";
+
+/// Chain-hash of drand testnet quicknet.
+pub const TESTNET_QUICKNET_CHAIN_HASH: &str =
+    "7672797f548f3f4748ac4bf3352fc6c6b6468c9ad40ad456a397545c6e2df5bf";
+
+/// Well known drand chains. Mainnet and Testnet can be supplied as root
+/// of trust to [Client::boot], with `chain.into()`.
+///
+/// Quicknet and TestnetQuicknet are info-only, their public key is on G2
+/// and does not fit [PublicKey], booting onto them fails. They can be
+/// addressed with [RelayRest], to read relay's routes and chain info.
+///
+/// [Client::boot]: crate::Client::boot
+/// [RelayRest]: crate::relay_rest::RelayRest
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Chain {
+    /// refer [MAINNET_CHAIN_HASH].
+    Mainnet,
+    /// refer [QUICKNET_CHAIN_HASH], info-only.
+    Quicknet,
+    /// refer [TESTNET_CHAIN_HASH].
+    Testnet,
+    /// refer [TESTNET_QUICKNET_CHAIN_HASH], info-only.
+    TestnetQuicknet,
+}
+
+impl Chain {
+    /// Return chain-hash of the chain, as hex string.
+    pub fn as_hex(&self) -> &'static str {
+        match self {
+            Chain::Mainnet => MAINNET_CHAIN_HASH,
+            Chain::Quicknet => QUICKNET_CHAIN_HASH,
+            Chain::Testnet => TESTNET_CHAIN_HASH,
+            Chain::TestnetQuicknet => TESTNET_QUICKNET_CHAIN_HASH,
+        }
+    }
+
+    /// Return chain-hash of the chain.
+    pub fn to_chain_hash(&self) -> ChainHash {
+        let mut bytes = [0_u8; ChainHash::SIZE];
+        // constants are valid hex of the right length.
+        hex::decode_to_slice(self.as_hex(), &mut bytes).ok();
+        ChainHash(bytes)
+    }
+}
+
+impl fmt::Display for Chain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+        let name = match self {
+            Chain::Mainnet => "mainnet",
+            Chain::Quicknet => "quicknet",
+            Chain::Testnet => "testnet",
+            Chain::TestnetQuicknet => "testnet-quicknet",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Chain {
+    type Err = Error;
+
+    /// Parse chain from its name, as displayed, or from its chain-hash.
+    fn from_str(s: &str) -> Result<Chain> {
+        let chains = [
+            Chain::Mainnet,
+            Chain::Quicknet,
+            Chain::Testnet,
+            Chain::TestnetQuicknet,
+        ];
+        let s = s.trim().to_lowercase();
+        match chains.iter().find(|c| c.to_string() == s || c.as_hex() == s) {
+            Some(chain) => Ok(*chain),
+            None => err_at!(Invalid, msg: format!("unknown chain {:?}", s)),
+        }
+    }
+}
+
+impl From<Chain> for Option<Vec<u8>> {
+    fn from(chain: Chain) -> Self {
+        Some(chain.to_chain_hash().as_bytes().to_vec())
+    }
+}
+
+#[cfg(test)]
+#[path = "types_test.rs"]
+mod types_test;
@@ -0,0 +1,42 @@
+use super::*;
+
+#[test]
+fn test_fixed_bytes() {
+    let hash = "8990e7a9aaed2ffed73dbd7092123d6f289930540d7651336225dc172e51b2ce";
+
+    let val = ChainHash::try_from(hash).unwrap();
+    assert_eq!(val.to_string(), hash);
+    assert_eq!(format!("{:?}", val), format!("ChainHash({})", hash));
+    assert_eq!(hex::encode(val), hash);
+    assert_eq!(val.as_bytes().len(), ChainHash::SIZE);
+    assert_eq!(Randomness::try_from(hash).unwrap().as_bytes(), val.as_bytes());
+
+    // bad length
+    assert!(ChainHash::try_from(&hash[2..]).is_err());
+    assert!(Signature::try_from(hash).is_err());
+    assert!(PublicKey::try_from(val.as_bytes()).is_err());
+    // bad hex
+    assert!(ChainHash::try_from(&hash.replace("8", "x")[..]).is_err());
+
+    assert_eq!(Signature::default().as_bytes(), &[0_u8; 96][..]);
+}
+
+#[test]
+fn test_chain() {
+    let chains = [
+        (Chain::Mainnet, "mainnet", MAINNET_CHAIN_HASH),
+        (Chain::Quicknet, "quicknet", QUICKNET_CHAIN_HASH),
+        (Chain::Testnet, "testnet", TESTNET_CHAIN_HASH),
+        (Chain::TestnetQuicknet, "testnet-quicknet", TESTNET_QUICKNET_CHAIN_HASH),
+    ];
+    for (chain, name, hash) in chains.iter() {
+        assert_eq!(chain.to_string(), *name);
+        assert_eq!(chain.to_chain_hash(), ChainHash::try_from(*hash).unwrap());
+        assert_eq!(name.parse::<Chain>().unwrap(), *chain);
+        assert_eq!(hash.parse::<Chain>().unwrap(), *chain);
+        let rot: Option<Vec<u8>> = (*chain).into();
+        assert_eq!(rot, Some(hex::decode(hash).unwrap()));
+    }
+    assert_eq!("  QuickNet ".parse::<Chain>().unwrap(), Chain::Quicknet);
+    assert!("devnet".parse::<Chain>().is_err());
+}
@@ -1,10 +1,12 @@
-/// Short form to compose Error values.
+/// Short form to compose Error values, prefixed with the location of the
+/// call site. Can be used by downstream crates as well, alternatively use
+/// [Error::new](crate::Error::new).
 ///
 /// Here are few possible ways:
 ///
 /// ```ignore
 /// use crate::Error;
-/// err_at!(Error::Invalid(String::default(), "bad argument"));
+/// err_at!(fallible_call_returning_crate_error())
 /// ```
 ///
 /// ```ignore
@@ -25,27 +27,24 @@
 #[macro_export]
 macro_rules! err_at {
     ($e:expr) => {{
-        use Error::*;
-
-        let p = format!("{}:{}", file!(), line!());
         match $e {
             Ok(val) => Ok(val),
-            Err(Fatal(_, s)) => Err(Fatal(p, s)),
-            Err(Invalid(_, s)) => Err(Invalid(p, s)),
-            Err(IOError(_, s)) => Err(IOError(p, s)),
-            Err(Parse(_, s)) => Err(Parse(p, s)),
+            Err(err) => {
+                let prefix = ::std::format!("{}:{}", file!(), line!());
+                Err($crate::Error::with_prefix(err, prefix))
+            }
         }
     }};
     ($v:ident, msg:$m:expr) => {{
-        let prefix = format!("{}:{}", file!(), line!());
-        Err(Error::$v(prefix, format!("{}", $m)))
+        let prefix = ::std::format!("{}:{}", file!(), line!());
+        Err($crate::Error::$v(prefix, ::std::format!("{}", $m)))
     }};
     ($v:ident, $e:expr) => {
         match $e {
             Ok(val) => Ok(val),
             Err(err) => {
-                let prefix = format!("{}:{}", file!(), line!());
-                Err(Error::$v(prefix, format!("{}", err)))
+                let prefix = ::std::format!("{}:{}", file!(), line!());
+                Err($crate::Error::$v(prefix, ::std::format!("{}", err)))
             }
         }
     };
@@ -53,8 +52,8 @@ macro_rules! err_at {
         match $e {
             Ok(val) => Ok(val),
             Err(err) => {
-                let prefix = format!("{}:{}", file!(), line!());
-                Err(Error::$v(prefix, format!("{} {}", $m, err)))
+                let prefix = ::std::format!("{}:{}", file!(), line!());
+                Err($crate::Error::$v(prefix, ::std::format!("{} {}", $m, err)))
             }
         }
     };
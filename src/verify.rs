@@ -1,27 +1,114 @@
-use crate::{Error, Random, Result};
+use std::{convert::TryFrom, fmt};
+
+use crate::{randomness_from_signature, Error, PublicKey, Random, Randomness, Result};
+
+/// Reason a round of randomness failed verification, carried by
+/// [Error::Verify], along with the offending round.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum VerifyError {
+    /// Public key or signature is not a valid curve point.
+    InvalidPoint { round: u128, msg: String },
+    /// Signature does not verify against the chain's public key, the BLS
+    /// pairing check failed.
+    PairingFailure { round: u128 },
+    /// Round's previous_signature does not match the signature of the
+    /// preceding round in the chain.
+    SignatureMismatch {
+        round: u128,
+        expected: Vec<u8>,
+        found: Vec<u8>,
+    },
+}
+
+impl VerifyError {
+    /// Return the round that failed verification.
+    pub fn to_round(&self) -> u128 {
+        match self {
+            VerifyError::InvalidPoint { round, .. } => *round,
+            VerifyError::PairingFailure { round } => *round,
+            VerifyError::SignatureMismatch { round, .. } => *round,
+        }
+    }
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VerifyError::InvalidPoint { round, msg } => {
+                write!(f, "round {} invalid point {}", round, msg)
+            }
+            VerifyError::PairingFailure { round } => write!(f, "round {} pairing failure", round),
+            VerifyError::SignatureMismatch {
+                round,
+                expected,
+                found,
+            } => {
+                let (x, y) = (hex::encode(expected), hex::encode(found));
+                write!(f, "round {} mismatch chain {:?} != {:?}", round, x, y)
+            }
+        }
+    }
+}
+
+/// Return randomness of round `r`, recomputed locally as the sha256 of its
+/// signature, irrespective of the randomness served along with it. Once
+/// the signature is verified, the recomputed randomness is as trustworthy
+/// as the signature, while the served randomness is only advisory, refer
+/// [Config::recompute_randomness].
+///
+/// [Config::recompute_randomness]: crate::Config::recompute_randomness
+pub fn recompute_randomness(r: &Random) -> Randomness {
+    randomness_from_signature(r.signature.as_bytes())
+}
+
+// verify `curr` round, chained to `previous_signature`, return
+// Error::Verify on failure.
+pub(crate) fn verify_chain(
+    pk: &PublicKey,
+    previous_signature: &[u8],
+    curr: &Random,
+) -> Result<()> {
+    let round = curr.round;
+    let prefix = || format!("{}:{}", file!(), line!());
 
-pub(crate) fn verify_chain(pk: &[u8], previous_signature: &[u8], curr: &Random) -> Result<bool> {
     if previous_signature != curr.previous_signature.as_slice() {
-        let s = hex::encode(previous_signature);
-        let p = hex::encode(&curr.previous_signature);
-        err_at!(NotSecure, msg: format!("mismatch chain {:?} != {:?}", s, p))?
+        let err = VerifyError::SignatureMismatch {
+            round,
+            expected: previous_signature.to_vec(),
+            found: curr.previous_signature.clone(),
+        };
+        return Err(Error::Verify(prefix(), err));
     }
 
-    let pk = {
-        let mut bytes: [u8; 48] = [0_u8; 48];
-        bytes[..].clone_from_slice(&pk);
-        err_at!(NotSecure, drand_verify::g1_from_fixed(bytes))?
+    let pk = match drand_verify::g1_from_fixed(pk.0) {
+        Ok(pk) => pk,
+        Err(err) => {
+            let msg = format!("public-key {}", err);
+            return Err(Error::Verify(prefix(), VerifyError::InvalidPoint { round, msg }));
+        }
     };
 
-    Ok(err_at!(
-        NotSecure,
-        drand_verify::verify(
-            &pk,
-            curr.round as u64,
-            &curr.previous_signature,
-            &curr.signature
-        )
-    )?)
+    let round_u64 = match u64::try_from(round) {
+        Ok(round_u64) => round_u64,
+        Err(_) => {
+            let msg = "round beyond u64".to_string();
+            return Err(Error::Verify(prefix(), VerifyError::InvalidPoint { round, msg }));
+        }
+    };
+    let res = drand_verify::verify(
+        &pk,
+        round_u64,
+        &curr.previous_signature,
+        curr.signature.as_bytes(),
+    );
+    match res {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(Error::Verify(prefix(), VerifyError::PairingFailure { round })),
+        Err(err) => {
+            let msg = format!("signature {}", err);
+            Err(Error::Verify(prefix(), VerifyError::InvalidPoint { round, msg }))
+        }
+    }
 }
 
 #[cfg(test)]
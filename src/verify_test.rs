@@ -16,8 +16,8 @@ fn test_verify() {
     let r1 = rt.block_on(endp.do_get(&client, Some(1))).unwrap();
     let r2 = rt.block_on(endp.do_get(&client, Some(2))).unwrap();
 
-    assert!(verify_chain(&info.public_key, &info.group_hash, &r1).unwrap());
-    assert!(verify_chain(&info.public_key, &r1.signature, &r2).unwrap());
+    verify_chain(&info.public_key, &info.group_hash, &r1).unwrap();
+    verify_chain(&info.public_key, r1.signature.as_bytes(), &r2).unwrap();
 }
 
 #[test]
@@ -34,3 +34,56 @@ fn test_randomness() {
         hex::encode(hasher.finalize().to_vec())
     );
 }
+
+#[test]
+fn test_verify_error() {
+    use crate::{fixtures::mainnet, Signature};
+
+    let (info, r1) = mainnet();
+    let (pk, group_hash) = (info.public_key, info.group_hash);
+    verify_chain(&pk, &group_hash, &r1).unwrap();
+
+    match verify_chain(&pk, &[0; 32], &r1) {
+        Err(Error::Verify(_, VerifyError::SignatureMismatch { round: 1, .. })) => (),
+        res => panic!("unexpected {:?}", res),
+    }
+
+    let mut bad = r1.clone();
+    bad.round = 2;
+    match verify_chain(&pk, &group_hash, &bad) {
+        Err(Error::Verify(_, VerifyError::PairingFailure { round: 2 })) => (),
+        res => panic!("unexpected {:?}", res),
+    }
+
+    let mut bad = r1.clone();
+    bad.signature = Signature([0xff; 96]);
+    match verify_chain(&pk, &group_hash, &bad) {
+        Err(Error::Verify(_, err @ VerifyError::InvalidPoint { .. })) => {
+            assert_eq!(err.to_round(), 1)
+        }
+        res => panic!("unexpected {:?}", res),
+    }
+
+    match verify_chain(&PublicKey([0xff; 48]), &group_hash, &r1) {
+        Err(Error::Verify(_, VerifyError::InvalidPoint { round: 1, .. })) => (),
+        res => panic!("unexpected {:?}", res),
+    }
+}
+
+#[test]
+fn test_recompute_randomness() {
+    use crate::fixtures::{mainnet_round1, MAINNET_RANDOMNESS_1};
+
+    let r = Random {
+        randomness: Randomness([0; 32]),
+        previous_signature: vec![],
+        ..mainnet_round1()
+    };
+    // served randomness is ignored.
+    assert_eq!(hex::encode(recompute_randomness(&r)), MAINNET_RANDOMNESS_1);
+    assert!(r.validate().is_err());
+
+    let mut r = r;
+    r.randomness = recompute_randomness(&r);
+    r.validate().unwrap();
+}
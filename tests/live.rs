@@ -0,0 +1,107 @@
+//! End-to-end tests against live drand networks. They depend on public
+//! relays and network access, so they are ignored by default, run them
+//! with:
+//!
+//! ```bash
+//! cargo test --test live -- --ignored
+//! ```
+
+use std::time;
+
+use drand::{
+    relay_rest::{RelayRest, Selector},
+    Category, Chain, Client, Config, DrandClient, Endpoint, SecurityPolicy,
+};
+
+const TESTNET_RELAY: &str = "https://pl-us.testnet.drand.sh";
+
+// rounds verified by each test, after the latest round.
+const VERIFY_ROUNDS: u128 = 5;
+
+fn new_client(name: &str, endpoints: Vec<Endpoint>, policy: SecurityPolicy) -> Client {
+    let mut config = Config::default();
+    config
+        .set_endpoints(endpoints)
+        .set_security_policy(policy)
+        .set_late_tolerance(Some(time::Duration::from_secs(10)));
+    Client::from_config(name, config)
+}
+
+// boot onto `chain`, get, watch a round and verify a small range.
+fn check_chain(mut client: Client, chain: Chain) {
+    client.boot(chain.into()).unwrap();
+    let info = client.to_info().unwrap();
+    assert_eq!(info.hash, chain.to_chain_hash());
+
+    let latest = client.get(None).unwrap();
+    latest.validate().unwrap();
+    let expected = info.round_at(time::SystemTime::now()).unwrap();
+    assert!(latest.round + 1 >= expected, "{} {}", latest.round, expected);
+
+    let prev = client.get(Some(latest.round - 1)).unwrap();
+    assert_eq!(prev.signature.as_bytes(), latest.previous_signature.as_slice());
+
+    let next = client.watch().unwrap().next().unwrap().unwrap();
+    assert!(next.round > latest.round, "{} {}", next.round, latest.round);
+    next.validate().unwrap();
+
+    let (from, till) = (latest.round - VERIFY_ROUNDS, latest.round);
+    let report = client.reverify(from, till).unwrap();
+    assert!(report.is_ok(), "{:?}", report.failures);
+
+    let usage = client.to_usage().unwrap();
+    assert!(usage.requests > 0);
+}
+
+#[test]
+#[ignore]
+fn test_live_mainnet_default() {
+    let endpoints = vec![Endpoint::HttpDrandApi, Endpoint::HttpCloudflare];
+    let client = new_client("mainnet", endpoints, SecurityPolicy::Assumed);
+    check_chain(client, Chain::Mainnet);
+}
+
+#[test]
+#[ignore]
+fn test_live_mainnet_window() {
+    let endpoints = vec![Endpoint::HttpDrandApi, Endpoint::HttpDrandApi2];
+    let window = time::Duration::from_secs(300);
+    let client = new_client("mainnet", endpoints, SecurityPolicy::Window(window));
+    check_chain(client, Chain::Mainnet);
+}
+
+#[test]
+#[ignore]
+fn test_live_testnet() {
+    let endpoints = vec![Endpoint::Http {
+        url: TESTNET_RELAY.to_string(),
+        category: Category::Origin,
+    }];
+    let client = new_client("testnet", endpoints, SecurityPolicy::Assumed);
+    check_chain(client, Chain::Testnet);
+}
+
+// Quicknet signs on G1 with its public key on G2, which Info and Random
+// do not hold, so Client cannot boot onto it and is not exercised here.
+// Only relay's routes and data for quicknet are checked, with RelayRest.
+#[test]
+#[ignore]
+fn test_live_quicknet() {
+    let relay = RelayRest::new("https://api.drand.sh").unwrap();
+    assert!(relay.chains().unwrap().contains(&Chain::Quicknet.to_chain_hash()));
+
+    let chain = Selector::Chain(Chain::Quicknet.to_chain_hash());
+    let info = relay.info(&chain).unwrap();
+    assert_eq!(info.hash, Chain::Quicknet.as_hex());
+    assert_eq!(info.period as u64, 3);
+    assert_eq!(info.scheme_id.as_deref(), Some("bls-unchained-g1-rfc9380"));
+
+    let latest = relay.latest(&chain).unwrap();
+    let r = relay.round(&chain, latest.round - 1).unwrap();
+    assert_eq!(r.round, latest.round - 1);
+    assert!(r.previous_signature.is_empty());
+
+    let v2 = Selector::Beacon("quicknet".to_string());
+    assert_eq!(relay.info_v2(&v2).unwrap().hash, info.hash);
+    assert_eq!(relay.round_v2(&v2, Some(r.round)).unwrap().signature, r.signature);
+}